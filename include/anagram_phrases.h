@@ -0,0 +1,89 @@
+/*
+ * C ABI for anagram-phrases, built as a `cdylib` via:
+ *
+ *     cargo build --release --features=ffi
+ *
+ * Each handle returned must be released via its corresponding `_free`
+ * function.  Strings are UTF-8 and NUL terminated, and those returned
+ * remain valid until their handle gets freed.
+ *
+ * Results consist of single word "transpositions" and multiple word
+ * "anagrams", where each phrase contains terms, and each term contains
+ * one or more alternative words sharing the same letters.
+ */
+
+#ifndef ANAGRAM_PHRASES_H
+#define ANAGRAM_PHRASES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Keep in sync with `AnagramStatus` within src/ffi.rs */
+typedef enum AnagramStatus {
+    ANAGRAM_OK = 0,
+    ANAGRAM_NULL_ARGUMENT = 1,
+    ANAGRAM_INVALID_UTF8 = 2,
+    ANAGRAM_PANIC = 3,
+    ANAGRAM_CHAR_OUT_OF_BOUNDS = 10,
+    ANAGRAM_MISMATCHED_CHARS = 11,
+    ANAGRAM_NO_FILE_PATH = 12,
+    ANAGRAM_PRIME_TOO_BIG = 13,
+    ANAGRAM_SERIALIZATION_ERROR = 14,
+    ANAGRAM_IO_ERROR = 15,
+    ANAGRAM_WORD_PRODUCT_NOT_FACTOR = 16,
+    ANAGRAM_WORD_PRODUCT_TOO_BIG = 17,
+    ANAGRAM_WORD_TOO_LONG = 18,
+    ANAGRAM_LANG_NOT_IMPLEMENTED = 19,
+    ANAGRAM_COMPRESSION_NOT_ENABLED = 20,
+    ANAGRAM_RULES_NOT_ENABLED = 21,
+    ANAGRAM_RULES_FILE = 22
+} AnagramStatus;
+
+/* Opaque handle of an entire word list loaded once for many queries */
+typedef struct AnagramLexicon AnagramLexicon;
+
+/* Opaque handle of results from one query */
+typedef struct AnagramResults AnagramResults;
+
+/* Load `n_paths` word list files for natural language `lang` (2 letter
+ * ISO code such as "EN") into new handle written to `out`. */
+AnagramStatus anagram_lexicon_load(const char *lang, const char *const *paths,
+                                   size_t n_paths, AnagramLexicon **out);
+
+void anagram_lexicon_free(AnagramLexicon *lexicon);
+
+/* Resolve `phrase` against `lexicon`, writing new handle to `out`.
+ * `max_words` of 0 defaults to one more than number of words within
+ * `phrase` and minimum of 3; `max_duration_ms` of 0 is unlimited. */
+AnagramStatus anagram_query(const AnagramLexicon *lexicon, const char *phrase,
+                            size_t max_words, uint64_t max_duration_ms,
+                            AnagramResults **out);
+
+void anagram_results_free(AnagramResults *results);
+
+/* Single word results; NULL when `i` is out of range */
+size_t anagram_results_transpositions_len(const AnagramResults *results);
+const char *anagram_results_transposition(const AnagramResults *results, size_t i);
+
+/* Multiple word results: word `k` of term `j` of phrase `i`;
+ * lengths are 0 and words are NULL when out of range */
+size_t anagram_results_anagrams_len(const AnagramResults *results);
+size_t anagram_results_terms_len(const AnagramResults *results, size_t i);
+size_t anagram_results_alternatives_len(const AnagramResults *results, size_t i,
+                                        size_t j);
+const char *anagram_results_word(const AnagramResults *results, size_t i, size_t j,
+                                 size_t k);
+
+/* Static description of `status`, which must not be freed; codes
+ * unknown to this version are described as "Unknown status" */
+const char *anagram_status_message(int status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ANAGRAM_PHRASES_H */
@@ -9,17 +9,30 @@ extern crate char_seq;
 
 extern crate anagram_phrases;
 
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
 use std::convert::From;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
 use std::sync::mpsc::channel;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use anagram_phrases::config::Config;
 use anagram_phrases::csv;
-use anagram_phrases::error::Result;
+use anagram_phrases::diff;
+use anagram_phrases::error::{AnagramError, Result};
 use anagram_phrases::json;
-use anagram_phrases::search::Search;
+use anagram_phrases::languages::Language;
+use anagram_phrases::primes::PMap;
+use anagram_phrases::search::{self, Search, Solution};
+use anagram_phrases::sources::Sources;
+#[cfg(feature = "sqlite")]
+use anagram_phrases::sqlite;
 use anagram_phrases::words;
 
 /// Default value when maximum number of words is NOT specified
@@ -28,16 +41,43 @@ const MIN_WORDS: usize = 3;
 /// Minimum duration for queries to run in seconds
 const MIN_DURATION_SECONDS: u64 = 9;
 
+/// File path indicating stdout for `--csv` and `--json`, or stdin for
+/// `--batch` and `--dict`
+const STDOUT_PATH: &str = "-";
+
 /// Find transpositions (single words) and anagrams (phrases).
 // See also: [Search].
 #[derive(Debug, Parser)]
-#[clap(max_term_width = 80)]
+#[clap(
+    max_term_width = 80,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
+)]
 struct Session {
     /// One or more words to be resolved as transpositions or anagrams.
     /// Only ASCII and ISO-8859-* character ranges supported as UTF-8.
-    #[clap(name = "WORD", required = true)]
+    #[clap(name = "WORD", required_unless_present_any = ["QUERIES", "interactive"])]
     input_phrase: Vec<String>,
 
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Resolve many queries from file containing one query per line,
+    /// or `-` for stdin, loading the dictionary only once.  Results
+    /// get labelled by query and streamed to stdout as NDJSON.
+    #[clap(short, long, name = "QUERIES", conflicts_with = "WORD")]
+    batch: Option<PathBuf>,
+
+    /// Prompt for phrases repeatedly, loading the dictionary only once.
+    /// Type `:help` at the prompt for commands changing settings.
+    #[clap(short = 'I', long, conflicts_with_all = ["WORD", "QUERIES"])]
+    interactive: bool,
+
+    /// Number of queries resolved concurrently via `--batch`.
+    /// Defaults to number of available processors.
+    #[clap(long, name = "JOBS")]
+    jobs: Option<usize>,
+
     /// Maximum duration allowed processing query in whole seconds.
     #[clap(short = 'D', long = "duration", name = "SECONDS")]
     max_duration: Option<u64>,
@@ -54,14 +94,32 @@ struct Session {
     #[clap(short, long, required = false)]
     quiet: bool,
 
-    /// Write sorted results as CSV to specified path and filename.
+    /// Write sorted results as CSV to specified path and filename,
+    /// or `-` for stdout which implies `--quiet`.
     #[clap(short, long, name = "FILE.csv")]
     csv: Option<PathBuf>,
 
-    /// Write sorted results as JSON to specified path and filename.
+    /// Arrangement of rows and columns for `--csv`: `sheet` has section
+    /// titles for humans, and `flat` has one row per result for tools.
+    #[clap(long, name = "LAYOUT", default_value = "sheet", ignore_case = true)]
+    csv_layout: csv::Layout,
+
+    /// Write sorted results as JSON to specified path and filename,
+    /// or `-` for stdout which implies `--quiet`.
     #[clap(short, long, name = "FILE.json")]
     json: Option<PathBuf>,
 
+    /// Append sorted results into SQLite database at specified path
+    /// and filename, which gets created when necessary.
+    #[cfg(feature = "sqlite")]
+    #[clap(long, name = "FILE.db")]
+    sqlite: Option<PathBuf>,
+
+    /// Compare results with those previously written via `--json`,
+    /// reporting phrases added or removed.
+    #[clap(long, name = "OLD.json")]
+    diff: Option<PathBuf>,
+
     #[command(flatten)]
     config: Config,
 
@@ -70,10 +128,54 @@ struct Session {
     verbose: bool,
 }
 
+/// Operations other than resolving an anagram.
+/// (Beware that a phrase beginning with one of these words gets
+/// interpreted as the command instead.)
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compare two files previously written via `--json`, reporting
+    /// phrases added or removed.
+    Diff {
+        /// Results from earlier run
+        #[clap(name = "OLD.json")]
+        old: PathBuf,
+        /// Results from later run
+        #[clap(name = "NEW.json")]
+        new: PathBuf,
+    },
+}
+
 /// Resolve a single anagram phrase or word from command-line parameters.
 // TODO refactor main() into smaller fn.
 fn main() -> Result<()> {
     let session = Session::parse();
+    let stdin = Path::new(STDOUT_PATH);
+    if (session.interactive || session.batch.as_deref() == Some(stdin))
+        && session.config.dict_file_paths.iter().any(|p| p == stdin)
+    {
+        Session::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "`--dict -` reads stdin, as do `--interactive` and `--batch -`",
+            )
+            .exit();
+    }
+    if let Some(Command::Diff { old, new }) = &session.command {
+        let old = read_json(old)?;
+        let new = read_json(new)?;
+        let changes = diff::compare(
+            (&old.transpositions, &old.anagrams),
+            (&new.transpositions, &new.anagrams),
+        );
+        print!("{changes}");
+        return Ok(());
+    }
+    if let Some(filepath) = &session.batch {
+        return batch(&session, filepath);
+    }
+    if session.interactive {
+        return interactive(&session);
+    }
     if session.verbose {
         // TODO set env log level
         println!("filter based upon rules for lang={:?}", session.config.lang);
@@ -81,16 +183,19 @@ fn main() -> Result<()> {
         println!("must include: {}", &session.must_include.join(", "));
         println!("must exclude: {}", &session.must_exclude.join(", "));
     }
-    let max_phrase_words = match session.config.max_phrase_words {
-        0 => std::cmp::max(session.input_phrase.len() + 1, MIN_WORDS),
-        n => n,
+    let max_phrase_words =
+        max_phrase_words(session.config.max_phrase_words, session.input_phrase.len());
+    // Streaming results would otherwise be interleaved with the export
+    let quiet = session.quiet || is_stdout(&session.csv) || is_stdout(&session.json);
+    let session = Session {
+        config: Config { max_phrase_words, ..session.config },
+        quiet,
+        ..session
     };
-    let session =
-        Session { config: Config { max_phrase_words, ..session.config }, ..session };
 
     let search =
         Search::query(&session.input_phrase, &session.must_include, &session.config)?;
-    let (dict, singles) = words::load_and_select(
+    let (dict, singles, sources) = words::load_and_select_sources(
         &session.config,
         &search.pattern,
         &search.essential,
@@ -138,20 +243,15 @@ fn main() -> Result<()> {
     // When `max_phrase_words` is exactly one (a transposition, not anagram/phrase),
     // it would have been found above while loading dictionary.
     if session.config.max_phrase_words > 1 {
-        let cache = words::Cache::init(&dict);
+        let cache = words::Cache::with_sources(&dict, &sources);
         let (tx, rx) = channel();
-        let duration = if let Some(d) = session.max_duration {
-            Some(Duration::new(std::cmp::max(d, MIN_DURATION_SECONDS), 0))
-        } else {
-            // TODO should be None; see search::SearchBuilder::brute_force()
-            Some(Duration::new(29, 0))
-        };
+        let duration = max_duration(session.max_duration);
         let builder = if session.quiet {
             search.enrich(&cache, None, duration)
         } else {
             search.enrich(&cache, Some(tx), duration)
         };
-        let results = std::thread::scope(move |s| -> Vec<Vec<Vec<String>>> {
+        let mut results = std::thread::scope(move |s| -> Vec<Vec<Vec<String>>> {
             if !session.quiet {
                 // Scoped threads get joined implicitly which guarantees completion
                 s.spawn(move || {
@@ -171,14 +271,24 @@ fn main() -> Result<()> {
             }
             builder.brute_force()
         });
+        sources.rank(&mut results);
 
         if session.verbose {
             println!("\nCandidate phrases:\nResults={}", results.len());
             let mut count = 0;
-            for n in 2..=session.config.max_phrase_words {
+            let max = session.config.max_phrase_words;
+            for n in 2..=search::max_word_count(max, &results) {
                 for terms in &results {
                     if terms.len() == n {
-                        println!("{:?}", terms);
+                        if sources.tags.len() > 1 {
+                            let tags: Vec<&str> = terms
+                                .iter()
+                                .filter_map(|t| t.first().and_then(|w| sources.tag(w)))
+                                .collect();
+                            println!("{:?} {:?}", terms, tags);
+                        } else {
+                            println!("{:?}", terms);
+                        }
                         count += 1;
                     }
                 }
@@ -191,22 +301,390 @@ fn main() -> Result<()> {
 
         if let Some(filepath) = session.json {
             let max = session.config.max_phrase_words;
-            json::write(&filepath, max, &singles, &results).map_err(|e| {
+            if filepath == Path::new(STDOUT_PATH) {
+                let stdout = std::io::stdout().lock();
+                json::write_tagged_to(stdout, max, &singles, &results, &sources)
+            } else {
+                File::create(&filepath).map_err(AnagramError::from).and_then(|f| {
+                    json::write_tagged_to(f, max, &singles, &results, &sources)
+                })
+            }
+            .map_err(|e| {
                 eprintln!("Unable to create JSON file {filepath:#?}, {e:?}");
                 e
             })?;
         }
         if let Some(filepath) = session.csv {
             let max = session.config.max_phrase_words;
-            csv::write(&filepath, max, &singles, &results).map_err(|e| {
+            let layout = session.csv_layout;
+            let alphabet = session.config.alphabet();
+            if filepath == Path::new(STDOUT_PATH) {
+                layout.write_to(
+                    std::io::stdout().lock(),
+                    max,
+                    alphabet,
+                    &singles,
+                    &results,
+                )
+            } else {
+                File::create(&filepath)
+                    .map_err(AnagramError::from)
+                    .and_then(|f| layout.write_to(f, max, alphabet, &singles, &results))
+            }
+            .map_err(|e| {
                 eprintln!("Unable to create CSV file {filepath:#?}, {e:?}");
                 e
             })?;
         }
+        #[cfg(feature = "sqlite")]
+        if let Some(filepath) = session.sqlite {
+            let max = session.config.max_phrase_words;
+            let query = session.input_phrase.join(" ");
+            let lang = format!("{:?}", session.config.lang);
+            let alphabet = session.config.alphabet();
+            sqlite::write(&filepath, &query, &lang, alphabet, max, &singles, &results)
+                .map_err(|e| {
+                    eprintln!("Unable to write SQLite file {filepath:#?}, {e:?}");
+                    e
+                })?;
+        }
+        if let Some(filepath) = session.diff {
+            let old = read_json(&filepath)?;
+            let changes =
+                diff::compare((&old.transpositions, &old.anagrams), (&singles, &results));
+            print!("{changes}");
+        }
+    }
+    Ok(())
+}
+
+/// Resolve many queries, one per line, with dictionary loaded once.
+/// Queries get distributed across `--jobs` worker threads, and each
+/// result is printed as one line of JSON (NDJSON) as it completes.
+fn batch(session: &Session, filepath: &Path) -> Result<()> {
+    let queries = read_queries(filepath).map_err(|e| {
+        eprintln!("Unable to read queries from {filepath:#?}, {e:?}");
+        e
+    })?;
+    let config = &session.config;
+    let (lexicon, sources) = words::preload_sources(config, session.verbose)?;
+    if session.verbose {
+        println!("queries: {}", queries.len());
+    }
+    // Streaming results would otherwise be interleaved with the export
+    let quiet = session.quiet || is_stdout(&session.csv) || is_stdout(&session.json);
+    let duration = max_duration(session.max_duration);
+    let jobs = session
+        .jobs
+        .or_else(|| std::thread::available_parallelism().map(NonZeroUsize::get).ok())
+        .unwrap_or(1)
+        .clamp(1, std::cmp::max(queries.len(), 1));
+
+    let next = AtomicUsize::new(0);
+    let solutions: Mutex<Vec<Option<Solution>>> = Mutex::new(vec![None; queries.len()]);
+    std::thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Relaxed);
+                let Some(input_phrase) = queries.get(i) else {
+                    break;
+                };
+                let max_phrase_words =
+                    max_phrase_words(config.max_phrase_words, input_phrase.len());
+                let config = Config { max_phrase_words, ..config.clone() };
+                let solution = search::resolve(
+                    input_phrase,
+                    &session.must_include,
+                    &session.must_exclude,
+                    &config,
+                    &lexicon,
+                    &sources,
+                    None,
+                    duration,
+                );
+                match solution {
+                    Ok(solution) => {
+                        if !quiet {
+                            match serde_json::to_string(&solution) {
+                                Ok(s) => println!("{s}"),
+                                Err(e) => println!("{solution:?} // {e}"),
+                            }
+                        }
+                        if let Ok(mut solutions) = solutions.lock() {
+                            solutions[i] = Some(solution);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Unable to resolve {:?}, {e:?}", input_phrase.join(" "))
+                    }
+                }
+            });
+        }
+    });
+    let solutions: Vec<Solution> = solutions
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let max = solutions
+        .iter()
+        .flat_map(|s| s.anagrams.iter().map(Vec::len))
+        .max()
+        .unwrap_or_default();
+    let alphabet = config.alphabet();
+    if let Some(filepath) = &session.json {
+        if filepath == Path::new(STDOUT_PATH) {
+            json::write_batch_to(std::io::stdout().lock(), max, &solutions)
+        } else {
+            File::create(filepath)
+                .map_err(AnagramError::from)
+                .and_then(|f| json::write_batch_to(f, max, &solutions))
+        }
+        .map_err(|e| {
+            eprintln!("Unable to create JSON file {filepath:#?}, {e:?}");
+            e
+        })?;
+    }
+    if let Some(filepath) = &session.csv {
+        if filepath == Path::new(STDOUT_PATH) {
+            csv::write_batch_to(std::io::stdout().lock(), max, alphabet, &solutions)
+        } else {
+            File::create(filepath)
+                .map_err(AnagramError::from)
+                .and_then(|f| csv::write_batch_to(f, max, alphabet, &solutions))
+        }
+        .map_err(|e| {
+            eprintln!("Unable to create CSV file {filepath:#?}, {e:?}");
+            e
+        })?;
+    }
+    #[cfg(feature = "sqlite")]
+    if let Some(filepath) = &session.sqlite {
+        let lang = format!("{:?}", config.lang);
+        for s in &solutions {
+            let (singles, anagrams) = (&s.transpositions, &s.anagrams);
+            sqlite::write(filepath, &s.query, &lang, alphabet, max, singles, anagrams)
+                .map_err(|e| {
+                    eprintln!("Unable to write SQLite file {filepath:#?}, {e:?}");
+                    e
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// Commands accepted at prompt of `--interactive` mode
+const REPL_HELP: &str = "\
+Type a word or phrase to be resolved, or one of these commands:
+  :include [WORD...]    Results must include these words; none to clear
+  :exclude [WORD...]    Results must exclude these words; none to clear
+  :max N                Maximum number of words in phrase; 0 for default
+  :lang XX [PATH...]    Natural language such as EN, ES, FR; reloads dictionary
+                        from PATH like --dict, or else the same word lists
+  :duration [SECONDS]   Maximum duration per query; none for default
+  :settings             Display current settings
+  :help                 Display this message
+  :quit                 Exit, as does end-of-file (Ctrl-D)";
+
+/// Prompt for phrases repeatedly with dictionary loaded only once.
+/// Settings such as `--include` may be changed via commands at the prompt.
+fn interactive(session: &Session) -> Result<()> {
+    let mut config = session.config.clone();
+    let mut must_include = session.must_include.clone();
+    let mut must_exclude = session.must_exclude.clone();
+    let mut seconds = session.max_duration;
+    let (mut lexicon, mut sources) = words::preload_sources(&config, session.verbose)?;
+    println!("{REPL_HELP}");
+    let stdin = std::io::stdin();
+    let mut line = String::new();
+    loop {
+        print!("> ");
+        std::io::stdout().flush()?;
+        line.clear();
+        if stdin.read_line(&mut line)? == 0 {
+            println!();
+            break;
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            let input_phrase: Vec<String> =
+                line.split_whitespace().map(ToString::to_string).collect();
+            let max_phrase_words =
+                max_phrase_words(config.max_phrase_words, input_phrase.len());
+            let config = Config { max_phrase_words, ..config.clone() };
+            let duration = max_duration(seconds);
+            if let Err(e) = resolve_streaming(
+                &input_phrase,
+                &must_include,
+                &must_exclude,
+                &config,
+                &lexicon,
+                &sources,
+                duration,
+            ) {
+                eprintln!("Unable to resolve {line:?}, {e:?}");
+            }
+            continue;
+        };
+        let mut args = command.split_whitespace();
+        let name = args.next().unwrap_or_default();
+        let args: Vec<String> = args.map(ToString::to_string).collect();
+        match name {
+            "quit" | "q" | "exit" => break,
+            "help" | "h" | "?" => println!("{REPL_HELP}"),
+            "include" | "i" => must_include = args,
+            "exclude" | "x" => must_exclude = args,
+            "max" | "m" => match args.first().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) => config.max_phrase_words = n,
+                _ => eprintln!("Usage: :max N"),
+            },
+            "duration" | "D" => match args.first().map(|n| n.parse::<u64>()) {
+                None => seconds = None,
+                Some(Ok(n)) => seconds = Some(n),
+                Some(Err(_)) => eprintln!("Usage: :duration [SECONDS]"),
+            },
+            "lang" | "l" => match args.first().map(|x| Language::from_str(x)) {
+                Some(Ok(lang)) if args[1..].iter().any(|p| p == STDOUT_PATH) => {
+                    eprintln!("Word list from stdin unavailable for {lang:?}")
+                }
+                Some(Ok(lang)) => {
+                    let previous = config.clone();
+                    config.lang = lang;
+                    if args.len() > 1 {
+                        config.dict_file_paths =
+                            args[1..].iter().map(PathBuf::from).collect();
+                    }
+                    match words::preload_sources(&config, session.verbose) {
+                        Ok((map, _)) if map.is_empty() => {
+                            eprintln!(
+                                "No {:?} words within {:?}; keeping {:?}",
+                                config.lang, config.dict_file_paths, previous.lang
+                            );
+                            config = previous;
+                        }
+                        Ok(loaded) => (lexicon, sources) = loaded,
+                        Err(e) => {
+                            eprintln!("Unable to reload dictionary, {e:?}");
+                            config = previous;
+                        }
+                    }
+                }
+                Some(Err(e)) => eprintln!("{e}: {:?}", args[0]),
+                None => eprintln!("Usage: :lang XX [PATH...]"),
+            },
+            "settings" | "show" => {
+                println!("lang: {:?}", config.lang);
+                println!("dict: {:?}", config.dict_file_paths);
+                println!("must include: {}", must_include.join(", "));
+                println!("must exclude: {}", must_exclude.join(", "));
+                println!("max words: {} (0 for default)", config.max_phrase_words);
+                println!("duration: {:?}", max_duration(seconds));
+            }
+            _ => eprintln!("Unknown command: {line:?}; try :help"),
+        }
     }
     Ok(())
 }
 
+/// Resolve one query against preloaded `lexicon`, printing
+/// transpositions first and then each anagram as it is found.
+/// Per-source `--limit` applies, but `--weight` cannot rank anagrams
+/// already printed.
+fn resolve_streaming(
+    input_phrase: &[String], must_include: &[String], must_exclude: &[String],
+    config: &Config, lexicon: &PMap, sources: &Sources, duration: Option<Duration>,
+) -> Result<()> {
+    let time = Instant::now();
+    let search = Search::query(input_phrase, must_include, config)?;
+    let (dict, mut singles) =
+        words::select(lexicon, &search.primes_product, must_exclude);
+    words::filter_multiword(config, &mut singles);
+    if !singles.is_empty() {
+        println!("{}", serde_json::to_string(&singles)?);
+    }
+    let mut count = 0;
+    if config.max_phrase_words > 1 {
+        let cache = words::Cache::with_sources(&dict, sources);
+        let (tx, rx) = channel();
+        let builder = search.enrich(&cache, Some(tx), duration);
+        let results = std::thread::scope(move |s| {
+            s.spawn(move || {
+                for phrase in rx.into_iter().map_while(|unique| unique) {
+                    match serde_json::to_string(&phrase) {
+                        Ok(s) => println!("{s}"),
+                        Err(e) => println!("{phrase:?} // {e}"),
+                    }
+                }
+            });
+            builder.brute_force()
+        });
+        count = results.len();
+    }
+    println!(
+        "# transpositions={} anagrams={count} elapsed={:.1?}",
+        singles.len(),
+        time.elapsed()
+    );
+    Ok(())
+}
+
+/// Read one query per line from file or stdin, skipping blank lines and
+/// comments beginning with `#`
+fn read_queries(filepath: &Path) -> Result<Vec<Vec<String>>> {
+    let reader: Box<dyn BufRead> = if filepath == Path::new(STDOUT_PATH) {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(filepath)?))
+    };
+    let mut queries = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        queries.push(line.split_whitespace().map(ToString::to_string).collect());
+    }
+    Ok(queries)
+}
+
+/// Honor `--max` unless zero, which defaults to one more than number of
+/// words within input phrase and a minimum of [MIN_WORDS]
+fn max_phrase_words(max: usize, input_words: usize) -> usize {
+    match max {
+        0 => std::cmp::max(input_words + 1, MIN_WORDS),
+        n => n,
+    }
+}
+
+/// Honor `--duration` but no less than [MIN_DURATION_SECONDS]
+fn max_duration(seconds: Option<u64>) -> Option<Duration> {
+    if let Some(d) = seconds {
+        Some(Duration::new(std::cmp::max(d, MIN_DURATION_SECONDS), 0))
+    } else {
+        // TODO should be None; see search::SearchBuilder::brute_force()
+        Some(Duration::new(29, 0))
+    }
+}
+
+/// Load results previously written via `--json`
+fn read_json(filepath: &Path) -> Result<json::Results> {
+    json::read(filepath).map_err(|e| {
+        eprintln!("Unable to read JSON file {filepath:#?}, {e:?}");
+        e
+    })
+}
+
+/// Whether `--csv` or `--json` was given as `-` for stdout
+fn is_stdout(filepath: &Option<PathBuf>) -> bool {
+    filepath.as_deref() == Some(Path::new(STDOUT_PATH))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,667 @@
+//! HTTP service for solving anagrams via JSON API, where word lists
+//! get preloaded once per natural language.
+//!
+//! Endpoints:
+//!
+//! - `POST /anagrams` with JSON body such as
+//!   `{"phrase": "new south wales", "lang": "EN", "max_words": 3}`;
+//!   see [AnagramRequest] for optional fields
+//! - `GET /transpositions?phrase=listen&lang=EN` for single words only
+//! - `GET /anagrams/stream?phrase=new+south+wales` streaming each
+//!   unique phrase as it is found via Server-Sent Events; see
+//!   [Service::stream]
+//!
+//! The first two respond with the same JSON as written by the CLI's
+//! `--json` option.
+//!
+//! This is merely porcelain, and as much functionality/plumbing as
+//! can be shared with CLI lives in the library.
+
+#[cfg(feature = "external-hasher")]
+extern crate char_seq;
+
+extern crate anagram_phrases;
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tiny_http::{HTTPVersion, Header, Method, Request, Response, Server};
+
+use anagram_phrases::config::Config;
+use anagram_phrases::error::{AnagramError, Result};
+use anagram_phrases::json;
+use anagram_phrases::languages::Language;
+use anagram_phrases::primes::PMap;
+use anagram_phrases::search::{self, Search};
+use anagram_phrases::sources::Sources;
+use anagram_phrases::words;
+
+/// Default value when maximum number of words is NOT specified
+const MIN_WORDS: usize = 3;
+
+/// Default maximum duration for queries to run in seconds, which is
+/// also the upper limit for `max_duration` of each request
+const MAX_DURATION_SECONDS: u64 = 29;
+
+/// Upper limit for size of request body in bytes
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// Interval between keep-alive comments of `GET /anagrams/stream` while
+/// no phrase gets found, which detects clients having disconnected
+const HEARTBEAT: Duration = Duration::from_secs(5);
+
+/// HTTP service finding transpositions (single words) and anagrams
+/// (phrases) via JSON API.
+#[derive(Debug, Parser)]
+#[clap(max_term_width = 80)]
+struct Options {
+    /// IP address and port for listening to HTTP requests.
+    #[clap(long, name = "ADDR", default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// Preload word list for a natural language as `LANG=PATH` such as
+    /// `fr=/usr/share/dict/french`.  Multiple allowed, including for
+    /// same language.  Without this, `--lang` and `--dict` get used.
+    #[clap(long, name = "LANG=PATH", value_parser = parse_lexicon)]
+    lexicon: Vec<(Language, PathBuf)>,
+
+    /// Maximum duration allowed processing each query in whole seconds,
+    /// which requests may only reduce.
+    #[clap(short = 'D', long = "duration", name = "SECONDS")]
+    max_duration: Option<u64>,
+
+    /// Number of requests handled concurrently, each by its own worker
+    /// thread.  Defaults to number of available processors.
+    #[clap(long, name = "WORKERS")]
+    workers: Option<usize>,
+
+    #[command(flatten)]
+    config: Config,
+
+    /// Display additional status information
+    #[clap(short, long)]
+    verbose: bool,
+}
+
+/// Body of `POST /anagrams` request
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnagramRequest {
+    /// One or more words to be resolved as transpositions or anagrams
+    phrase: String,
+    /// 2 letter ISO code for natural language, defaulting to `--lang`
+    #[serde(default)]
+    lang: Option<String>,
+    /// Maximum number of words in phrase, where 0 or omitted defaults
+    /// to one more than number of words within `phrase` and minimum of 3
+    #[serde(default)]
+    max_words: usize,
+    /// Maximum duration in whole seconds, limited by `--duration`
+    #[serde(default)]
+    max_duration: Option<u64>,
+    /// Results must include these words
+    #[serde(default)]
+    include: Vec<String>,
+    /// Results must exclude these words
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Word list and its configuration for one natural language
+struct Lexicon {
+    config: Config,
+    map: PMap,
+    sources: Sources,
+}
+
+/// Query validated against preloaded word lists
+struct Query<'s> {
+    lexicon: &'s Lexicon,
+    input_phrase: Vec<String>,
+    config: Config,
+}
+
+/// Shared state across all requests
+struct Service {
+    lexicons: BTreeMap<Language, Lexicon>,
+    default_lang: Language,
+    max_duration: Duration,
+    workers: usize,
+}
+
+/// Load word lists and then serve HTTP requests until terminated.
+fn main() -> Result<()> {
+    let options = Options::parse();
+    let service = Service::new(&options)?;
+    let server = Server::http(&options.listen).map_err(|e| {
+        eprintln!("Unable to listen on {}, {e}", options.listen);
+        AnagramError::IoError(std::io::Error::other(e))
+    })?;
+    if options.verbose {
+        println!("Listening on http://{}", server.server_addr());
+    }
+    service.serve(&server);
+    Ok(())
+}
+
+impl Service {
+    /// Preload each word list specified via `options`
+    fn new(options: &Options) -> Result<Service> {
+        let mut paths: BTreeMap<Language, Vec<PathBuf>> = BTreeMap::new();
+        if options.lexicon.is_empty() {
+            paths.insert(
+                options.config.lang.clone(),
+                options.config.dict_file_paths.clone(),
+            );
+        }
+        for (lang, path) in &options.lexicon {
+            paths.entry(lang.clone()).or_default().push(path.clone());
+        }
+        let mut lexicons = BTreeMap::new();
+        for (lang, dict_file_paths) in paths {
+            let config =
+                Config { lang: lang.clone(), dict_file_paths, ..options.config.clone() };
+            let (map, sources) = words::preload_sources(&config, options.verbose)?;
+            lexicons.insert(lang, Lexicon { config, map, sources });
+        }
+        let default_lang = if lexicons.contains_key(&options.config.lang) {
+            options.config.lang.clone()
+        } else {
+            lexicons.keys().next().cloned().unwrap_or_default()
+        };
+        let seconds = options.max_duration.unwrap_or(MAX_DURATION_SECONDS);
+        let workers = options.workers.unwrap_or_else(|| {
+            std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        });
+        Ok(Service {
+            lexicons,
+            default_lang,
+            max_duration: Duration::from_secs(seconds),
+            workers: workers.max(1),
+        })
+    }
+
+    /// Handle requests via a fixed number of worker threads, where
+    /// further requests wait for the next available worker
+    fn serve(&self, server: &Server) {
+        std::thread::scope(|s| {
+            for _ in 0..self.workers {
+                s.spawn(|| {
+                    for request in server.incoming_requests() {
+                        self.respond(request);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Route `request` and send response
+    fn respond(&self, mut request: Request) {
+        let path = request.url().split('?').next().unwrap_or_default().to_string();
+        if (request.method(), path.as_str()) == (&Method::Get, "/anagrams/stream") {
+            return self.stream(request);
+        }
+        let (status, body) = match (request.method(), path.as_str()) {
+            (Method::Post, "/anagrams") => {
+                let mut body = String::new();
+                match request.as_reader().take(MAX_BODY_BYTES).read_to_string(&mut body) {
+                    Ok(_) => self.anagrams(&body),
+                    Err(e) => error(400, &e.to_string()),
+                }
+            }
+            (Method::Get, "/transpositions") => self.transpositions(request.url()),
+            (_, "/anagrams") | (_, "/anagrams/stream") | (_, "/transpositions") => {
+                error(405, "Method not allowed")
+            }
+            _ => error(404, "Not found"),
+        };
+        send_json(request, status, body)
+    }
+
+    /// Resolve JSON body of `POST /anagrams`; see [AnagramRequest].
+    fn anagrams(&self, body: &str) -> (u16, String) {
+        let request: AnagramRequest = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(e) => return error(400, &e.to_string()),
+        };
+        let duration = match request.max_duration {
+            Some(seconds) => self.max_duration.min(Duration::from_secs(seconds)),
+            None => self.max_duration,
+        };
+        self.resolve(
+            &request.phrase,
+            request.lang.as_deref(),
+            request.max_words,
+            &request.include,
+            &request.exclude,
+            duration,
+        )
+    }
+
+    /// Resolve `GET /transpositions?phrase=...&lang=...` for single words
+    fn transpositions(&self, url: &str) -> (u16, String) {
+        let Some(phrase) = query_param(url, "phrase") else {
+            return error(400, "Missing query parameter: phrase");
+        };
+        let lang = query_param(url, "lang");
+        self.resolve(&phrase, lang.as_deref(), 1, &[], &[], self.max_duration)
+    }
+
+    /// Stream results of `GET /anagrams/stream?phrase=...` as
+    /// Server-Sent Events, such as for a browser's `EventSource`.
+    ///
+    /// Optional query parameters have the same names and semantics as
+    /// fields of [AnagramRequest], where `include` and `exclude` may be
+    /// repeated once per word.
+    ///
+    /// Events are `transpositions` (once, if any), then `anagram` per
+    /// unique phrase as each is found, and finally `summary` with
+    /// counts and elapsed time.  Each `data` field contains JSON.
+    ///
+    /// While no phrase gets found, a comment gets sent every [HEARTBEAT]
+    /// such that closing the connection cancels the search within that
+    /// interval, otherwise `max_duration` still applies.
+    fn stream(&self, request: Request) {
+        let url = request.url().to_string();
+        let (query, duration) = match self.stream_query(&url) {
+            Ok(parsed) => parsed,
+            Err((status, body)) => return send_json(request, status, body),
+        };
+        let include = query_params(&url, "include");
+        let exclude = query_params(&url, "exclude");
+        let time = Instant::now();
+        let search = match Search::query(&query.input_phrase, &include, &query.config) {
+            Ok(search) => search,
+            Err(e) => return send_json(request, 422, error(422, &e.to_string()).1),
+        };
+        let (dict, mut singles) =
+            words::select(&query.lexicon.map, &search.primes_product, &exclude);
+        words::filter_multiword(&query.config, &mut singles);
+        let version = request.http_version().clone();
+        let Ok(mut events) = EventStream::open(request.into_writer(), version) else {
+            return;
+        };
+        if !singles.is_empty() && events.send("transpositions", &singles).is_err() {
+            return;
+        }
+        let mut count = 0;
+        if query.config.max_phrase_words > 1 {
+            let cache = words::Cache::with_sources(&dict, &query.lexicon.sources);
+            let (tx, rx) = channel();
+            let cancel = AtomicBool::new(false);
+            let builder = search
+                .enrich(&cache, Some(tx), Some(duration))
+                .with_cancel(Some(&cancel));
+            let (results, returned) = std::thread::scope(|s| {
+                let cancel = &cancel;
+                let handle = s.spawn(move || {
+                    loop {
+                        let sent = match rx.recv_timeout(HEARTBEAT) {
+                            Ok(Some(phrase)) => events.send("anagram", &phrase),
+                            Ok(None) | Err(RecvTimeoutError::Disconnected) => break,
+                            Err(RecvTimeoutError::Timeout) => {
+                                events.comment("keep-alive")
+                            }
+                        };
+                        if sent.is_err() {
+                            cancel.store(true, Ordering::Relaxed);
+                            return None;
+                        }
+                    }
+                    Some(events)
+                });
+                (builder.brute_force(), handle.join())
+            });
+            count = results.len();
+            let Ok(Some(returned)) = returned else {
+                return;
+            };
+            events = returned;
+        }
+        let summary = serde_json::json!({
+            "query": query.input_phrase.join(" "),
+            "transpositions": singles.len(),
+            "anagrams": count,
+            "elapsed_ms": time.elapsed().as_millis(),
+        });
+        let _ = events.send("summary", &summary).and_then(|()| events.close());
+    }
+
+    /// Parse query string of `GET /anagrams/stream`, returning query
+    /// and maximum duration
+    fn stream_query(
+        &self, url: &str,
+    ) -> std::result::Result<(Query<'_>, Duration), (u16, String)> {
+        let Some(phrase) = query_param(url, "phrase") else {
+            return Err(error(400, "Missing query parameter: phrase"));
+        };
+        let lang = query_param(url, "lang");
+        let max_words = number_param(url, "max_words")?.unwrap_or(0);
+        let duration = match number_param(url, "max_duration")? {
+            Some(seconds) => self.max_duration.min(Duration::from_secs(seconds as u64)),
+            None => self.max_duration,
+        };
+        Ok((self.prepare(&phrase, lang.as_deref(), max_words)?, duration))
+    }
+
+    /// Validate query against preloaded word list for `lang`, and
+    /// apply defaults.
+    fn prepare(
+        &self, phrase: &str, lang: Option<&str>, max_words: usize,
+    ) -> std::result::Result<Query<'_>, (u16, String)> {
+        let lang = match lang.map(Language::from_str) {
+            None => self.default_lang.clone(),
+            Some(Ok(lang)) => lang,
+            Some(Err(e)) => return Err(error(400, &e.to_string())),
+        };
+        let Some(lexicon) = self.lexicons.get(&lang) else {
+            return Err(error(
+                404,
+                &format!("No word list loaded for language {lang:?}"),
+            ));
+        };
+        let input_phrase: Vec<String> =
+            phrase.split_whitespace().map(ToString::to_string).collect();
+        if input_phrase.is_empty() {
+            return Err(error(400, "Empty phrase"));
+        }
+        let max_phrase_words = match max_words {
+            0 => std::cmp::max(input_phrase.len() + 1, MIN_WORDS),
+            n => n,
+        };
+        let config = Config { max_phrase_words, ..lexicon.config.clone() };
+        Ok(Query { lexicon, input_phrase, config })
+    }
+
+    /// Resolve query against preloaded word list for `lang`, returning
+    /// HTTP status code and JSON body.
+    fn resolve(
+        &self, phrase: &str, lang: Option<&str>, max_words: usize, include: &[String],
+        exclude: &[String], duration: Duration,
+    ) -> (u16, String) {
+        let query = match self.prepare(phrase, lang, max_words) {
+            Ok(query) => query,
+            Err(response) => return response,
+        };
+        let solution = search::resolve(
+            &query.input_phrase,
+            include,
+            exclude,
+            &query.config,
+            &query.lexicon.map,
+            &query.lexicon.sources,
+            None,
+            Some(duration),
+        );
+        let max_phrase_words = query.config.max_phrase_words;
+        let mut body: Vec<u8> = vec![];
+        match solution.and_then(|s| {
+            json::write_to(&mut body, max_phrase_words, &s.transpositions, &s.anagrams)
+        }) {
+            Ok(()) => (200, String::from_utf8_lossy(&body).to_string()),
+            Err(e) => error(422, &e.to_string()),
+        }
+    }
+}
+
+/// Writer of Server-Sent Events directly to the client's socket,
+/// flushing each event so that it arrives as soon as it is found.
+///
+/// Chunked transfer encoding gets applied for HTTP/1.1 clients, since
+/// that of [Response] buffers too much for streaming.
+struct EventStream {
+    writer: Box<dyn Write + Send>,
+    chunked: bool,
+}
+
+impl EventStream {
+    /// Write response headers
+    fn open(
+        mut writer: Box<dyn Write + Send>, version: HTTPVersion,
+    ) -> std::io::Result<EventStream> {
+        let chunked = version >= HTTPVersion(1, 1);
+        let encoding =
+            if chunked { "Transfer-Encoding: chunked" } else { "Connection: close" };
+        write!(
+            writer,
+            "HTTP/{version} 200 OK\r\nContent-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n{encoding}\r\n\r\n"
+        )?;
+        writer.flush()?;
+        Ok(EventStream { writer, chunked })
+    }
+
+    /// Write one event named `event` with `data` as JSON
+    fn send<T: Serialize>(&mut self, event: &str, data: &T) -> std::io::Result<()> {
+        let frame = format!("event: {event}\ndata: {}\n\n", serde_json::to_string(data)?);
+        self.write_frame(&frame)
+    }
+
+    /// Write a comment, which clients ignore, such as for keep-alive
+    fn comment(&mut self, text: &str) -> std::io::Result<()> {
+        self.write_frame(&format!(": {text}\n\n"))
+    }
+
+    fn write_frame(&mut self, frame: &str) -> std::io::Result<()> {
+        if self.chunked {
+            write!(self.writer, "{:x}\r\n{frame}\r\n", frame.len())?;
+        } else {
+            self.writer.write_all(frame.as_bytes())?;
+        }
+        self.writer.flush()
+    }
+
+    /// Signal end of stream
+    fn close(mut self) -> std::io::Result<()> {
+        if self.chunked {
+            self.writer.write_all(b"0\r\n\r\n")?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Send `body` as JSON with HTTP `status` code
+fn send_json(request: Request, status: u16, body: String) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header");
+    let response =
+        Response::from_string(body).with_status_code(status).with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        eprintln!("Unable to send response, {e:?}");
+    }
+}
+
+/// HTTP status code with JSON body containing `message`
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+/// Extract and decode first value of `name` from query string of `url`
+fn query_param(url: &str, name: &str) -> Option<String> {
+    query_params(url, name).into_iter().next()
+}
+
+/// Extract and decode all values of `name` from query string of `url`
+fn query_params(url: &str, name: &str) -> Vec<String> {
+    let Some((_, query)) = url.split_once('?') else {
+        return vec![];
+    };
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+        .collect()
+}
+
+/// Extract non-negative integer value of `name` from query string of `url`
+fn number_param(
+    url: &str, name: &str,
+) -> std::result::Result<Option<usize>, (u16, String)> {
+    match query_param(url, name) {
+        None => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| error(400, &format!("Expected number for {name}: {value}"))),
+    }
+}
+
+/// Decode `application/x-www-form-urlencoded` value such as "new+south%20wales"
+fn percent_decode(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex: Vec<u8> = iter.by_ref().take(2).collect();
+                match std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                {
+                    Some(decoded) => bytes.push(decoded),
+                    None => {
+                        bytes.push(b'%');
+                        bytes.extend(hex);
+                    }
+                }
+            }
+            _ => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Parse `LANG=PATH` for `--lexicon`
+fn parse_lexicon(value: &str) -> std::result::Result<(Language, PathBuf), String> {
+    let (lang, path) = value.split_once('=').ok_or("Expected LANG=PATH")?;
+    let lang = Language::from_str(lang).map_err(|e| format!("{e}: {lang}"))?;
+    Ok((lang, PathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    #[test]
+    fn verify_cli() {
+        use clap::CommandFactory;
+        Options::command().debug_assert()
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!("new south wales", percent_decode("new+south%20wales"));
+        assert_eq!("vérité", percent_decode("v%C3%A9rit%C3%A9"));
+        assert_eq!("100%", percent_decode("100%"));
+        let url = "/transpositions?lang=en&phrase=listen";
+        assert_eq!(Some("listen".to_string()), query_param(url, "phrase"));
+        assert_eq!(None, query_param(url, "max"));
+        let url = "/anagrams/stream?phrase=x&exclude=a&exclude=b";
+        assert_eq!(vec!["a", "b"], query_params(url, "exclude"));
+        assert_eq!(Ok(None), number_param(url, "max_words"));
+        assert_eq!(400, number_param("/?max_words=x", "max_words").unwrap_err().0);
+    }
+
+    /// Send raw HTTP/1.0 `request` to server and return status and body
+    fn send(server: &Server, request: &str) -> (u16, String) {
+        let addr = server.server_addr().to_ip().expect("TCP listener");
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    #[test]
+    fn localhost() {
+        let path = std::env::temp_dir()
+            .join(format!("anagram-phrases-httpd-{}.txt", std::process::id()));
+        std::fs::write(&path, "enlist\nlisten\nsilent\nnewel\nwashouts\n").unwrap();
+        let options = Options::parse_from([
+            "anagram-phrases-httpd",
+            "--listen=127.0.0.1:0",
+            &format!("--lexicon=en={}", path.to_string_lossy()),
+        ]);
+        let service = Service::new(&options).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let server = Server::http(&options.listen).unwrap();
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                // Exactly as many requests as sent below:
+                for request in server.incoming_requests().take(7) {
+                    service.respond(request);
+                }
+            });
+
+            let (status, body) =
+                send(&server, "GET /transpositions?phrase=tinsel HTTP/1.0\r\n\r\n");
+            assert_eq!(200, status);
+            assert_eq!(
+                r#"{"transpositions":["enlist","listen","silent"],"anagrams":[]}"#,
+                body
+            );
+
+            let json =
+                r#"{"phrase": "new south wales", "lang": "en", "max_duration": 1}"#;
+            let request = format!(
+                "POST /anagrams HTTP/1.0\r\nContent-Length: {}\r\n\r\n{json}",
+                json.len()
+            );
+            let (status, body) = send(&server, &request);
+            assert_eq!(200, status);
+            assert_eq!(
+                r#"{"transpositions":[],"anagrams":[[["newel"],["washouts"]]]}"#,
+                body
+            );
+
+            let (status, _) = send(
+                &server,
+                "GET /transpositions?phrase=tinsel&lang=fr HTTP/1.0\r\n\r\n",
+            );
+            assert_eq!(404, status);
+            let (status, _) = send(&server, "GET /anagrams HTTP/1.0\r\n\r\n");
+            assert_eq!(405, status);
+            let (status, _) = send(&server, "GET / HTTP/1.0\r\n\r\n");
+            assert_eq!(404, status);
+
+            let (status, body) = send(
+                &server,
+                "GET /anagrams/stream?phrase=new+south+wales&max_duration=1 HTTP/1.0\r\n\r\n",
+            );
+            assert_eq!(200, status);
+            let mut events = body.split("\n\n");
+            assert_eq!(
+                Some(
+                    r#"event: anagram
+data: [["newel"],["washouts"]]"#
+                ),
+                events.next()
+            );
+            assert!(events.next().unwrap().starts_with(
+                r#"event: summary
+data: {"anagrams":1,"#
+            ));
+
+            // Chunked transfer encoding for HTTP/1.1
+            let (status, body) = send(
+                &server,
+                "GET /anagrams/stream?phrase=tinsel&max_words=1 HTTP/1.1\r\nConnection: close\r\n\r\n",
+            );
+            assert_eq!(200, status);
+            assert!(body.contains(r#"data: ["enlist","listen","silent"]"#));
+            assert!(body.ends_with("\r\n0\r\n\r\n"));
+        });
+    }
+}
@@ -0,0 +1,89 @@
+//! User supplied lists of words to exclude from results, such as
+//! offensive words or trademarks, and of words to allow regardless.
+//!
+//! Both get matched ignoring upper/lower case and diacritics, per
+//! [Casing] of the language such as Turkish dotless ı.
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::fold;
+use crate::languages::Casing;
+use crate::words;
+
+/// Lines beginning with this are comments within blocklist files
+pub const COMMENT: char = '#';
+
+/// Words blocked from dictionary, and words allowed which override
+/// both the blocklist and filters per language such as for single
+/// letters or uppercase.
+#[derive(Clone, Debug, Default)]
+pub struct Blocklist {
+    blocked: HashSet<String>,
+    allowed: HashSet<String>,
+    casing: Casing,
+}
+
+impl Blocklist {
+    /// Load one word per line from each of `exclude_files` and
+    /// `allow_files`, skipping blank lines and comments.  Files may be
+    /// compressed; see [words::open_file].  Words get lowercased per
+    /// `casing` of the language.
+    pub fn load<P: AsRef<Path>>(
+        exclude_files: &[P], allow_files: &[P], casing: Casing,
+    ) -> Result<Blocklist> {
+        let mut blocklist = Blocklist { casing, ..Blocklist::default() };
+        for path in exclude_files {
+            read_into(words::open_file(path.as_ref())?, casing, &mut blocklist.blocked)?;
+        }
+        for path in allow_files {
+            read_into(words::open_file(path.as_ref())?, casing, &mut blocklist.allowed)?;
+        }
+        Ok(blocklist)
+    }
+
+    /// Constructor from words already in memory
+    pub fn from_words<S: AsRef<str>>(
+        blocked: &[S], allowed: &[S], casing: Casing,
+    ) -> Blocklist {
+        Blocklist {
+            blocked: blocked.iter().map(|w| key(w.as_ref(), casing)).collect(),
+            allowed: allowed.iter().map(|w| key(w.as_ref(), casing)).collect(),
+            casing,
+        }
+    }
+
+    /// True when `word` is blocked and not also allowed
+    pub fn is_blocked(&self, word: &str) -> bool {
+        !self.blocked.is_empty() && {
+            let key = key(word, self.casing);
+            self.blocked.contains(&key) && !self.allowed.contains(&key)
+        }
+    }
+
+    /// True when `word` is allowed explicitly
+    pub fn is_allowed(&self, word: &str) -> bool {
+        !self.allowed.is_empty() && self.allowed.contains(&key(word, self.casing))
+    }
+}
+
+/// Equivalent of `word` for matching regardless of case and diacritics,
+/// lowercased prior to folding such that Turkish İ becomes i
+fn key(word: &str, casing: Casing) -> String {
+    fold::diacritics(&casing.lowercase(word), casing)
+}
+
+fn read_into<R: BufRead>(
+    reader: R, casing: Casing, words: &mut HashSet<String>,
+) -> Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        let word = line.trim();
+        if !word.is_empty() && !word.starts_with(COMMENT) {
+            words.insert(key(word, casing));
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,17 @@
 //! Configuration (excluding query)
 
 use clap::{Parser, ValueEnum};
+use glob::Pattern;
 use std::convert::From;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
-use crate::languages::{Encoding, Language};
+use crate::blocklist::Blocklist;
+use crate::error::Result;
+use crate::languages::{Encoding, Language, Normalization, Region, Rules};
+use crate::primes::Alphabet;
+use crate::sources;
+use crate::words::{self, Dedup};
 
 /// Where to look for dictionary/lexicon files supplied by OS distribution
 /// such as those compatible with ispell or GNU aspell.
@@ -18,7 +24,7 @@ static DEFAULT_DICT_FILES: LazyLock<Vec<PathBuf>> =
 /// one per natural language supported.
 // For augmenting this struct with CLI args, see:
 // https://docs.rs/clap/latest/clap/_derive/index.html#mixing-builder-and-derive-apis
-#[derive(Debug, Default, Parser)]
+#[derive(Clone, Debug, Default, Parser)]
 #[clap(max_term_width = 80)]
 pub struct Config {
     /// Specify 2 letter ISO code for natural language such as EN for
@@ -32,12 +38,41 @@ pub struct Config {
     )]
     pub lang: CliLanguage,
 
+    /// Specify region such as GB or US for selecting rules specific to
+    /// it from `--rules` file.
+    #[clap(long, required = false, default_value = "Any", ignore_case = true)]
+    pub region: CliRegion,
+
+    /// TOML file (or JSON when named `*.json`) overriding built-in
+    /// filter rules per language and region, such as allowed short
+    /// words, allowed capitalized words, minimum length and stopwords.
+    #[clap(long = "rules", name = "RULES_FILE")]
+    pub rules_file: Option<PathBuf>,
+
     /// Dictionary file containing one word per line as plain-text.
     /// Some files suitable for `ispell` or GNU `aspell` are compatible.
+    /// Use `-` for stdin, or a directory for every file within it.
+    /// Tag as `TAG=PATH` such as `slang=slang.txt` to identify the
+    /// source of words within results; otherwise, tagged by file name.
     #[clap(short, long="dict", name = "PATH",
            default_values=DEFAULT_DICT_FILES.iter().map(|p| p.as_os_str()))]
     pub dict_file_paths: Vec<PathBuf>,
 
+    /// Load only files matching this pattern such as `*-words.[1-5]?`
+    /// from directories specified via `--dict`.
+    #[clap(long = "dict-glob", name = "PATTERN")]
+    pub dict_glob: Option<Pattern>,
+
+    /// Weight of word lists tagged TAG via `--dict`, listing phrases
+    /// with words from those of greater weight first.  Defaults to 1.
+    #[clap(long = "weight", name = "TAG=WEIGHT", value_parser = sources::parse_tagged::<f64>)]
+    pub source_weights: Vec<(String, f64)>,
+
+    /// At most N words from word lists tagged TAG via `--dict` within
+    /// each phrase, such as `slang=1`.
+    #[clap(long = "limit", name = "TAG=N", value_parser = sources::parse_tagged::<usize>)]
+    pub source_limits: Vec<(String, usize)>,
+
     /// Specify encoding of dictionary/word list file.
     #[clap(short, long, ignore_case = true, name = "X", default_value = "UTF_8")]
     pub encoding: CliEncoding,
@@ -47,6 +82,18 @@ pub struct Config {
     #[clap(short, long = "max", default_value = "0", name = "N")]
     pub max_phrase_words: usize,
 
+    /// Count each multi-word lexeme from dictionary such as "ice cream"
+    /// as this many words toward `--max`.  Defaults to its number of
+    /// words.
+    #[clap(long, name = "COST",
+           value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub multiword_cost: Option<usize>,
+
+    /// Exclude multi-word lexemes such as "ice cream" or "New York"
+    /// found within dictionary.
+    #[clap(long, conflicts_with = "COST")]
+    pub no_multiword: bool,
+
     /// Include dictionary words containing single letters, which may
     /// contribute to noisy results.  Alternatively, specify `--lang`
     /// allowing exceptions of `a` for English, `y` for Spanish, etc.
@@ -56,31 +103,185 @@ pub struct Config {
 
     /// Include dictionary words containing uppercase, which indicates
     /// being a proper name.  Alternatively, specify `--lang` to allow
-    /// "I" as an exception for English; etc.
+    /// "I" as an exception for English; etc.  No effect for German,
+    /// where capitalized words are always included as nouns.
     // v0.5: name changed and value inverted since v0.4.0 `Options`
     #[clap(short = 'u', long = "upcase")]
     pub include_upcase: bool,
+
+    /// Ignore diacritics while matching letters, such that "vérité"
+    /// may be an anagram of words with plain "e".  Results retain
+    /// original spelling.
+    #[clap(long)]
+    pub fold_diacritics: bool,
+
+    /// Unicode canonical form of query and dictionary words within
+    /// results.  Letters with accents match the same either way.
+    #[clap(long, ignore_case = true, name = "FORM", default_value = "NFC")]
+    pub normalization: CliNormalization,
+
+    /// Treat dictionary words differing only by upper/lower case as
+    /// duplicates, keeping only the first loaded.
+    #[clap(long)]
+    pub dedup_case: bool,
+
+    /// Treat dictionary words differing only by diacritics such as
+    /// "élan" and "elan" as duplicates, keeping only the first loaded.
+    #[clap(long)]
+    pub dedup_diacritics: bool,
+
+    /// File of words to exclude from dictionary, one per line, such as
+    /// offensive words or trademarks.  Ignores upper/lower case and
+    /// diacritics.
+    #[clap(long = "exclude-file", name = "EXCLUDE_FILE")]
+    pub exclude_files: Vec<PathBuf>,
+
+    /// File of words to include regardless of `--exclude-file` and
+    /// filters per `--lang`, one per line.  Ignores upper/lower case
+    /// and diacritics.
+    #[clap(long = "allow-file", name = "ALLOW_FILE")]
+    pub allow_files: Vec<PathBuf>,
+}
+
+impl Config {
+    /// Word list files of `dict_file_paths` after replacing each
+    /// directory with files inside it matching `dict_glob`.
+    ///
+    /// See fn [words::dict_files].
+    pub fn dict_files(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.dict_sources()?.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Same as [Config::dict_files] but with tag of each file, as
+    /// specified via `TAG=PATH` or otherwise per [sources::split_tag].
+    pub fn dict_sources(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut files = vec![];
+        for path in &self.dict_file_paths {
+            let (tag, path) = sources::split_tag(path);
+            for file in words::dict_files(&[path], self.dict_glob.as_ref())? {
+                files.push((tag.clone(), file));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Filter rules per `lang` and `region`, built-in or from `rules_file`
+    ///
+    /// See fn [Rules::load].
+    pub fn rules(&self) -> Result<Rules> {
+        let rules = Rules::load(self.rules_file.as_deref(), &self.lang, &self.region)?;
+        let normalization = self.normalization;
+        Ok(Rules { fold_diacritics: self.fold_diacritics, normalization, ..rules })
+    }
+
+    /// Mapping of letters to primes per `lang`, `fold_diacritics` and
+    /// `normalization`
+    pub fn alphabet(&self) -> Alphabet {
+        Alphabet {
+            script: self.lang.script(),
+            expansions: self.lang.expansions(),
+            casing: self.lang.casing(),
+            fold_diacritics: self.fold_diacritics,
+            normalization: self.normalization,
+        }
+    }
+
+    /// Words blocked or allowed per `exclude_files` and `allow_files`
+    pub fn blocklist(&self) -> Result<Blocklist> {
+        Blocklist::load(&self.exclude_files, &self.allow_files, self.lang.casing())
+    }
+
+    /// Merging of duplicate words while loading word lists
+    pub fn dedup(&self) -> Dedup {
+        Dedup {
+            case: self.dedup_case,
+            diacritics: self.dedup_diacritics,
+            casing: self.lang.casing(),
+        }
+    }
 }
 
 // Adding clap::ValueEnum to language::Language and language::Encoding
 // smelled like a leaky abstraction because that's part of our library,
-// which shouldn't need to use `clap`.  Therefore, CliLanguage and
-// CliEncoding exist as type aliases here.
+// which shouldn't need to use `clap`.  Therefore, CliLanguage,
+// CliRegion, CliEncoding and CliNormalization exist as type aliases here.
 
 type CliLanguage = Language;
+type CliRegion = Region;
 type CliEncoding = Encoding;
+type CliNormalization = Normalization;
 
 impl ValueEnum for CliLanguage {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Language::Any, Language::EN, Language::ES, Language::FR]
+        &[
+            Language::Any,
+            Language::DE,
+            Language::EL,
+            Language::EN,
+            Language::ES,
+            Language::FR,
+            Language::IT,
+            Language::NL,
+            Language::PT,
+            Language::RU,
+            Language::TR,
+            Language::UK,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         let value = match self {
             Language::Any => clap::builder::PossibleValue::new("Any"),
+            Language::DE => clap::builder::PossibleValue::new("DE"),
+            Language::EL => clap::builder::PossibleValue::new("EL"),
             Language::EN => clap::builder::PossibleValue::new("EN"),
             Language::ES => clap::builder::PossibleValue::new("ES"),
             Language::FR => clap::builder::PossibleValue::new("FR"),
+            Language::IT => clap::builder::PossibleValue::new("IT"),
+            Language::NL => clap::builder::PossibleValue::new("NL"),
+            Language::PT => clap::builder::PossibleValue::new("PT"),
+            Language::RU => clap::builder::PossibleValue::new("RU"),
+            Language::TR => clap::builder::PossibleValue::new("TR"),
+            Language::UK => clap::builder::PossibleValue::new("UK"),
+        };
+        Some(value)
+    }
+}
+
+impl ValueEnum for CliRegion {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Region::Any,
+            Region::AT,
+            Region::BE,
+            Region::BR,
+            Region::CA,
+            Region::CH,
+            Region::DE,
+            Region::GB,
+            Region::IT,
+            Region::NL,
+            Region::PT,
+            Region::UK,
+            Region::US,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let value = match self {
+            Region::Any => clap::builder::PossibleValue::new("Any"),
+            Region::AT => clap::builder::PossibleValue::new("AT"),
+            Region::BE => clap::builder::PossibleValue::new("BE"),
+            Region::BR => clap::builder::PossibleValue::new("BR"),
+            Region::CA => clap::builder::PossibleValue::new("CA"),
+            Region::CH => clap::builder::PossibleValue::new("CH"),
+            Region::DE => clap::builder::PossibleValue::new("DE"),
+            Region::GB => clap::builder::PossibleValue::new("GB"),
+            Region::IT => clap::builder::PossibleValue::new("IT"),
+            Region::NL => clap::builder::PossibleValue::new("NL"),
+            Region::PT => clap::builder::PossibleValue::new("PT"),
+            Region::UK => clap::builder::PossibleValue::new("UK"),
+            Region::US => clap::builder::PossibleValue::new("US"),
         };
         Some(value)
     }
@@ -99,3 +300,17 @@ impl ValueEnum for CliEncoding {
         Some(value)
     }
 }
+
+impl ValueEnum for CliNormalization {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Normalization::NFC, Normalization::NFD]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let value = match self {
+            Normalization::NFC => clap::builder::PossibleValue::new("NFC"),
+            Normalization::NFD => clap::builder::PossibleValue::new("NFD"),
+        };
+        Some(value)
+    }
+}
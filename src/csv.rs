@@ -1,17 +1,108 @@
 //! Write results as CSV file: common separated values.
 
+use clap::ValueEnum;
 use csv::WriterBuilder;
+use std::fs::File;
+use std::io::Write;
 use std::path::PathBuf;
 
 use crate::error::Result;
+use crate::primes::Alphabet;
+use crate::search::{self, Solution};
 
-/// Persist transpositions and anagrams as JSON file.
+/// Arrangement of rows and columns within CSV file.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Layout {
+    /// Human-oriented sheet with section titles and blank separator
+    /// rows, where each word of a phrase gets its own column.
+    #[default]
+    Sheet,
+    /// Machine-friendly table with fixed header and one row per result,
+    /// suitable for pandas or spreadsheets; see [FLAT_HEADER].
+    Flat,
+}
+
+impl ValueEnum for Layout {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Layout::Sheet, Layout::Flat]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let value = match self {
+            Layout::Sheet => clap::builder::PossibleValue::new("sheet"),
+            Layout::Flat => clap::builder::PossibleValue::new("flat"),
+        };
+        Some(value)
+    }
+}
+
+/// Column names for [Layout::Flat]
+pub const FLAT_HEADER: [&str; 6] =
+    ["kind", "word_count", "letters", "phrase", "alternatives", "score"];
+
+/// One record of [Layout::Flat] corresponding to [FLAT_HEADER].
+struct FlatRow<'a> {
+    /// Either "transposition" or "anagram"
+    kind: &'a str,
+    word_count: usize,
+    /// Count of alphabetic characters within phrase
+    letters: usize,
+    /// First of each word's alternatives, separated by space
+    phrase: String,
+    /// All alternatives per word separated by `|`, and words by space
+    alternatives: String,
+    /// See fn [search::score]
+    score: usize,
+}
+
+impl FlatRow<'_> {
+    /// Fields in same sequence as [FLAT_HEADER]
+    fn record(&self) -> [String; 6] {
+        [
+            self.kind.to_string(),
+            self.word_count.to_string(),
+            self.letters.to_string(),
+            self.phrase.clone(),
+            self.alternatives.clone(),
+            self.score.to_string(),
+        ]
+    }
+}
+
+impl Layout {
+    /// Write transpositions and anagrams as CSV to any `writer` using
+    /// this layout, where `alphabet` of their language is for counting
+    /// letters of [Layout::Flat].
+    pub fn write_to<W: Write>(
+        &self, writer: W, max: usize, alphabet: Alphabet, singles: &[String],
+        phrases: &[Vec<Vec<String>>],
+    ) -> Result<()> {
+        match self {
+            Layout::Sheet => write_to(writer, max, singles, phrases),
+            Layout::Flat => write_flat_to(writer, max, alphabet, singles, phrases),
+        }
+    }
+}
+
+/// Persist transpositions and anagrams as CSV file.
+///
+/// See also: fn [write_to].
 pub fn write(
     filepath: &PathBuf, max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
+) -> Result<()> {
+    write_to(File::create(filepath)?, max, singles, phrases)
+}
+
+/// Write transpositions and anagrams as CSV to any `writer` such as
+/// [std::io::Stdout], an in-memory buffer or a compressed stream.
+///
+/// See [Layout::Sheet].
+pub fn write_to<W: Write>(
+    writer: W, max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
 ) -> Result<()> {
     let empty_row: Vec<&str> = vec![];
 
-    let mut f = WriterBuilder::new().flexible(true).from_path(filepath)?;
+    let mut f = WriterBuilder::new().flexible(true).from_writer(writer);
 
     f.write_record(["Transpositions"])?;
     for transposition in singles {
@@ -24,7 +115,7 @@ pub fn write(
     f.write_record(&empty_row)?;
     let limit = phrases.len();
     let mut count = 0;
-    for n in 2..=max {
+    for n in 2..=search::max_word_count(max, phrases) {
         f.write_record(&[format!("{n} words")])?;
         f.write_record(&empty_row)?;
         for terms in phrases {
@@ -39,6 +130,88 @@ pub fn write(
         }
         f.write_record(&empty_row)?;
     }
+    f.flush()?;
 
     Ok(())
 }
+
+/// Write transpositions and anagrams as CSV to any `writer` with one
+/// row per result, sorted by number of words in phrase, and letters
+/// counted per `alphabet` of their language.
+///
+/// See [Layout::Flat].
+pub fn write_flat_to<W: Write>(
+    writer: W, max: usize, alphabet: Alphabet, singles: &[String],
+    phrases: &[Vec<Vec<String>>],
+) -> Result<()> {
+    let mut f = WriterBuilder::new().from_writer(writer);
+    f.write_record(FLAT_HEADER)?;
+    for row in flat_rows(max, alphabet, singles, phrases) {
+        f.write_record(row.record())?;
+    }
+    f.flush()?;
+    Ok(())
+}
+
+/// Write results of many queries as CSV to any `writer` using
+/// [Layout::Flat] but with an additional first column, `query`.
+pub fn write_batch_to<W: Write>(
+    writer: W, max: usize, alphabet: Alphabet, solutions: &[Solution],
+) -> Result<()> {
+    let mut f = WriterBuilder::new().from_writer(writer);
+    f.write_record(std::iter::once("query").chain(FLAT_HEADER))?;
+    for solution in solutions {
+        let rows = flat_rows(max, alphabet, &solution.transpositions, &solution.anagrams);
+        for row in rows {
+            f.write_record(std::iter::once(solution.query.clone()).chain(row.record()))?;
+        }
+    }
+    f.flush()?;
+    Ok(())
+}
+
+/// Rows of [Layout::Flat] with transpositions first and then anagrams
+/// sorted by number of words in phrase
+fn flat_rows<'a>(
+    max: usize, alphabet: Alphabet, singles: &'a [String],
+    phrases: &'a [Vec<Vec<String>>],
+) -> Vec<FlatRow<'a>> {
+    let mut rows = Vec::with_capacity(singles.len() + phrases.len());
+    for transposition in singles {
+        let terms = [vec![transposition.clone()]];
+        rows.push(FlatRow {
+            kind: "transposition",
+            word_count: 1,
+            letters: search::letters(&terms, alphabet),
+            phrase: transposition.clone(),
+            alternatives: transposition.clone(),
+            score: search::score(&terms, alphabet),
+        });
+    }
+
+    let limit = phrases.len();
+    let mut count = 0;
+    for n in 2..=search::max_word_count(max, phrases) {
+        for terms in phrases {
+            if terms.len() == n {
+                let firsts: Vec<&str> =
+                    terms.iter().filter_map(|x| x.first()).map(String::as_str).collect();
+                let alternatives: Vec<String> =
+                    terms.iter().map(|x| x.join("|")).collect();
+                rows.push(FlatRow {
+                    kind: "anagram",
+                    word_count: n,
+                    letters: search::letters(terms, alphabet),
+                    phrase: firsts.join(" "),
+                    alternatives: alternatives.join(" "),
+                    score: search::score(terms, alphabet),
+                });
+                count += 1;
+            }
+        }
+        if count == limit {
+            break;
+        }
+    }
+    rows
+}
@@ -0,0 +1,108 @@
+//! Compare results of two runs, such as before and after switching
+//! dictionary word lists or regions.
+//!
+//! Phrases are compared by their words regardless of word order,
+//! whereby each word includes its alternatives with same product of
+//! primes.  Therefore, gaining or losing an alternative such as "lea"
+//! for "ale" reports that phrase as both removed and added.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Differences between results of an earlier run and a later run.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct Changes {
+    /// Single words found only within later run
+    pub transpositions_added: Vec<String>,
+    /// Single words found only within earlier run
+    pub transpositions_removed: Vec<String>,
+    /// Phrases found only within later run
+    pub anagrams_added: Vec<Vec<Vec<String>>>,
+    /// Phrases found only within earlier run
+    pub anagrams_removed: Vec<Vec<Vec<String>>>,
+}
+
+impl Changes {
+    /// Whether both runs produced identical results
+    pub fn is_empty(&self) -> bool {
+        self.transpositions_added.is_empty()
+            && self.transpositions_removed.is_empty()
+            && self.anagrams_added.is_empty()
+            && self.anagrams_removed.is_empty()
+    }
+}
+
+/// Compare `old` results with `new` results, each as a tuple of
+/// transpositions and anagrams such as from [crate::json::read] or
+/// [crate::search::SearchBuilder::brute_force].
+///
+/// Returned lists are sorted alphabetically.
+pub fn compare(
+    old: (&[String], &[Vec<Vec<String>>]), new: (&[String], &[Vec<Vec<String>>]),
+) -> Changes {
+    let (transpositions_added, transpositions_removed) = difference(
+        old.0.iter().map(|w| (w.clone(), w)),
+        new.0.iter().map(|w| (w.clone(), w)),
+    );
+    let (anagrams_added, anagrams_removed) = difference(
+        old.1.iter().map(|p| (phrase_key(p), p)),
+        new.1.iter().map(|p| (phrase_key(p), p)),
+    );
+    Changes {
+        transpositions_added,
+        transpositions_removed,
+        anagrams_added,
+        anagrams_removed,
+    }
+}
+
+/// Return tuple of values only in `new` and only in `old`, respectively.
+fn difference<'a, T: Clone + 'a>(
+    old: impl Iterator<Item = (String, &'a T)>,
+    new: impl Iterator<Item = (String, &'a T)>,
+) -> (Vec<T>, Vec<T>) {
+    let old: BTreeMap<String, &T> = old.collect();
+    let new: BTreeMap<String, &T> = new.collect();
+    let added =
+        new.iter().filter(|(k, _)| !old.contains_key(*k)).map(|(_, &v)| v.clone());
+    let removed =
+        old.iter().filter(|(k, _)| !new.contains_key(*k)).map(|(_, &v)| v.clone());
+    (added.collect(), removed.collect())
+}
+
+/// Canonical form of `phrase` independent of order of words and of
+/// order of alternatives; e.g., "ale|lea snouts whew".
+fn phrase_key(phrase: &[Vec<String>]) -> String {
+    let mut terms: Vec<String> = phrase
+        .iter()
+        .map(|alternatives| {
+            let mut words: Vec<&str> = alternatives.iter().map(String::as_str).collect();
+            words.sort_unstable();
+            words.join("|")
+        })
+        .collect();
+    terms.sort_unstable();
+    terms.join(" ")
+}
+
+impl std::fmt::Display for Changes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Transpositions added: {}", self.transpositions_added.len())?;
+        for word in &self.transpositions_added {
+            writeln!(f, "+ {word}")?;
+        }
+        writeln!(f, "Transpositions removed: {}", self.transpositions_removed.len())?;
+        for word in &self.transpositions_removed {
+            writeln!(f, "- {word}")?;
+        }
+        writeln!(f, "Anagrams added: {}", self.anagrams_added.len())?;
+        for phrase in &self.anagrams_added {
+            writeln!(f, "+ {}", phrase_key(phrase))?;
+        }
+        writeln!(f, "Anagrams removed: {}", self.anagrams_removed.len())?;
+        for phrase in &self.anagrams_removed {
+            writeln!(f, "- {}", phrase_key(phrase))?;
+        }
+        Ok(())
+    }
+}
@@ -35,6 +35,15 @@ pub enum AnagramError {
     #[error("The requested language is not implemented")]
     LangNotImplemented,
 
+    #[error("Decompressing word list requires compile-time feature: {0}")]
+    CompressionNotEnabled(&'static str),
+
+    #[error("Loading rules file requires compile-time feature: rules")]
+    RulesNotEnabled,
+
+    #[error("Unable to parse rules file: {0}")]
+    RulesFile(String),
+
     #[cfg(feature = "cli")]
     #[error("Unable to generate JSON payload")]
     JsonPayload(#[from] serde_json::Error),
@@ -42,4 +51,8 @@ pub enum AnagramError {
     #[cfg(feature = "cli")]
     #[error("Unable to generate CSV payload")]
     CsvPayload(#[from] csv::Error),
+
+    #[cfg(feature = "sqlite")]
+    #[error("Unable to write SQLite database")]
+    Sqlite(#[from] rusqlite::Error),
 }
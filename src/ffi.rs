@@ -0,0 +1,411 @@
+//! C ABI for embedding within mobile apps and other languages such as
+//! via JNI, Kotlin, Swift or Python `ctypes`.
+//!
+//! See `include/anagram_phrases.h` for the corresponding C header.
+//!
+//! Each handle returned must be released via its corresponding `_free`
+//! function.  Strings are UTF-8 and NUL terminated, and those returned
+//! remain valid until their handle gets freed.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::AnagramError;
+use crate::languages::Language;
+use crate::primes::PMap;
+use crate::search;
+use crate::sources::Sources;
+use crate::words;
+
+/// Default value when maximum number of words is NOT specified
+const MIN_WORDS: usize = 3;
+
+/// Status codes returned by functions of the C ABI, where each
+/// variant of [AnagramError] maps to one of these.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnagramStatus {
+    Ok = 0,
+    NullArgument = 1,
+    InvalidUtf8 = 2,
+    Panic = 3,
+    CharOutOfBounds = 10,
+    MismatchedChars = 11,
+    NoFilePath = 12,
+    PrimeTooBig = 13,
+    SerializationError = 14,
+    IoError = 15,
+    WordProductNotFactor = 16,
+    WordProductTooBig = 17,
+    WordTooLong = 18,
+    LangNotImplemented = 19,
+    CompressionNotEnabled = 20,
+    RulesNotEnabled = 21,
+    RulesFile = 22,
+}
+
+impl AnagramStatus {
+    /// Every variant, for mapping status codes received from C
+    const ALL: [AnagramStatus; 17] = [
+        AnagramStatus::Ok,
+        AnagramStatus::NullArgument,
+        AnagramStatus::InvalidUtf8,
+        AnagramStatus::Panic,
+        AnagramStatus::CharOutOfBounds,
+        AnagramStatus::MismatchedChars,
+        AnagramStatus::NoFilePath,
+        AnagramStatus::PrimeTooBig,
+        AnagramStatus::SerializationError,
+        AnagramStatus::IoError,
+        AnagramStatus::WordProductNotFactor,
+        AnagramStatus::WordProductTooBig,
+        AnagramStatus::WordTooLong,
+        AnagramStatus::LangNotImplemented,
+        AnagramStatus::CompressionNotEnabled,
+        AnagramStatus::RulesNotEnabled,
+        AnagramStatus::RulesFile,
+    ];
+
+    /// Variant of status `code`, or `None` when unknown such as from a
+    /// header of another version
+    pub fn from_code(code: c_int) -> Option<AnagramStatus> {
+        AnagramStatus::ALL.into_iter().find(|status| *status as c_int == code)
+    }
+}
+
+impl From<&AnagramError> for AnagramStatus {
+    fn from(error: &AnagramError) -> AnagramStatus {
+        match error {
+            AnagramError::CharOutOfBounds => AnagramStatus::CharOutOfBounds,
+            AnagramError::MismatchedChars => AnagramStatus::MismatchedChars,
+            AnagramError::NoFilePath => AnagramStatus::NoFilePath,
+            AnagramError::PrimeTooBig => AnagramStatus::PrimeTooBig,
+            AnagramError::SerializationError => AnagramStatus::SerializationError,
+            AnagramError::IoError(_) => AnagramStatus::IoError,
+            AnagramError::WordProductNotFactor => AnagramStatus::WordProductNotFactor,
+            AnagramError::WordProductTooBig => AnagramStatus::WordProductTooBig,
+            AnagramError::WordTooLong => AnagramStatus::WordTooLong,
+            AnagramError::LangNotImplemented => AnagramStatus::LangNotImplemented,
+            AnagramError::CompressionNotEnabled(_) => {
+                AnagramStatus::CompressionNotEnabled
+            }
+            AnagramError::RulesNotEnabled => AnagramStatus::RulesNotEnabled,
+            AnagramError::RulesFile(_) => AnagramStatus::RulesFile,
+            #[cfg(feature = "cli")]
+            AnagramError::JsonPayload(_) | AnagramError::CsvPayload(_) => {
+                AnagramStatus::SerializationError
+            }
+            #[cfg(feature = "sqlite")]
+            AnagramError::Sqlite(_) => AnagramStatus::SerializationError,
+        }
+    }
+}
+
+/// Opaque handle of an entire word list loaded once for many queries
+pub struct AnagramLexicon {
+    config: Config,
+    map: PMap,
+}
+
+/// Opaque handle of results from one query
+pub struct AnagramResults {
+    transpositions: Vec<CString>,
+    anagrams: Vec<Vec<Vec<CString>>>,
+}
+
+/// Load word list files for natural language `lang` (2 letter ISO code
+/// such as "EN") into new handle written to `out`.
+///
+/// # Safety
+///
+/// `lang` must be a NUL terminated string, `paths` must point to
+/// `n_paths` NUL terminated strings, and `out` must be valid for writes.
+/// Release handle via [anagram_lexicon_free].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_lexicon_load(
+    lang: *const c_char, paths: *const *const c_char, n_paths: usize,
+    out: *mut *mut AnagramLexicon,
+) -> AnagramStatus {
+    guard(|| {
+        if lang.is_null() || paths.is_null() || out.is_null() {
+            return AnagramStatus::NullArgument;
+        }
+        if n_paths == 0 {
+            return AnagramStatus::NoFilePath;
+        }
+        let lang = match to_str(lang).map(Language::from_str) {
+            Ok(Ok(lang)) => lang,
+            Ok(Err(e)) => return AnagramStatus::from(&e),
+            Err(status) => return status,
+        };
+        let mut dict_file_paths = Vec::with_capacity(n_paths);
+        for path in std::slice::from_raw_parts(paths, n_paths) {
+            if path.is_null() {
+                return AnagramStatus::NullArgument;
+            }
+            match to_str(*path) {
+                Ok(path) => dict_file_paths.push(PathBuf::from(path)),
+                Err(status) => return status,
+            }
+        }
+        let config = Config { lang, dict_file_paths, ..Config::default() };
+        let files: Vec<&Path> =
+            config.dict_file_paths.iter().map(PathBuf::as_path).collect();
+        let result = config.rules().and_then(|rules| {
+            words::preload(
+                &files,
+                &rules,
+                &config.encoding,
+                config.include_short,
+                config.include_upcase,
+                config.dedup(),
+                &config.blocklist()?,
+                false,
+            )
+        });
+        match result {
+            Ok(map) => {
+                *out = Box::into_raw(Box::new(AnagramLexicon { config, map }));
+                AnagramStatus::Ok
+            }
+            Err(e) => AnagramStatus::from(&e),
+        }
+    })
+}
+
+/// Release handle previously returned by [anagram_lexicon_load].
+///
+/// # Safety
+///
+/// `lexicon` must be NULL or a handle not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn anagram_lexicon_free(lexicon: *mut AnagramLexicon) {
+    if !lexicon.is_null() {
+        drop(Box::from_raw(lexicon));
+    }
+}
+
+/// Resolve `phrase` against `lexicon`, writing new handle of results
+/// to `out`.
+///
+/// Limits: `max_words` of 0 defaults to one more than number of words
+/// within `phrase` and minimum of 3; `max_duration_ms` of 0 is unlimited.
+///
+/// # Safety
+///
+/// `lexicon` must be a handle from [anagram_lexicon_load], `phrase`
+/// must be a NUL terminated string, and `out` must be valid for writes.
+/// Release results via [anagram_results_free].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_query(
+    lexicon: *const AnagramLexicon, phrase: *const c_char, max_words: usize,
+    max_duration_ms: u64, out: *mut *mut AnagramResults,
+) -> AnagramStatus {
+    guard(|| {
+        if lexicon.is_null() || phrase.is_null() || out.is_null() {
+            return AnagramStatus::NullArgument;
+        }
+        let lexicon = &*lexicon;
+        let input_phrase: Vec<String> = match to_str(phrase) {
+            Ok(phrase) => phrase.split_whitespace().map(ToString::to_string).collect(),
+            Err(status) => return status,
+        };
+        let max_phrase_words = match max_words {
+            0 => std::cmp::max(input_phrase.len() + 1, MIN_WORDS),
+            n => n,
+        };
+        let config = Config { max_phrase_words, ..lexicon.config.clone() };
+        let max_duration = match max_duration_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        };
+        let result = search::resolve(
+            &input_phrase,
+            &[],
+            &[],
+            &config,
+            &lexicon.map,
+            &Sources::default(),
+            None,
+            max_duration,
+        );
+        match result {
+            Ok(solution) => {
+                let results = AnagramResults {
+                    transpositions: solution
+                        .transpositions
+                        .into_iter()
+                        .map(to_cstring)
+                        .collect(),
+                    anagrams: solution
+                        .anagrams
+                        .into_iter()
+                        .map(|phrase| {
+                            phrase
+                                .into_iter()
+                                .map(|alternatives| {
+                                    alternatives.into_iter().map(to_cstring).collect()
+                                })
+                                .collect()
+                        })
+                        .collect(),
+                };
+                *out = Box::into_raw(Box::new(results));
+                AnagramStatus::Ok
+            }
+            Err(e) => AnagramStatus::from(&e),
+        }
+    })
+}
+
+/// Release handle previously returned by [anagram_query].
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_free(results: *mut AnagramResults) {
+    if !results.is_null() {
+        drop(Box::from_raw(results));
+    }
+}
+
+/// Number of single word results.
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_transpositions_len(
+    results: *const AnagramResults,
+) -> usize {
+    results.as_ref().map_or(0, |r| r.transpositions.len())
+}
+
+/// Single word result at index `i`, or NULL when out of range.
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_transposition(
+    results: *const AnagramResults, i: usize,
+) -> *const c_char {
+    as_ptr(results.as_ref().and_then(|r| r.transpositions.get(i)))
+}
+
+/// Number of multiple word results (phrases).
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_anagrams_len(
+    results: *const AnagramResults,
+) -> usize {
+    results.as_ref().map_or(0, |r| r.anagrams.len())
+}
+
+/// Number of terms within phrase at index `i`, or 0 when out of range.
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_terms_len(
+    results: *const AnagramResults, i: usize,
+) -> usize {
+    results.as_ref().and_then(|r| r.anagrams.get(i)).map_or(0, Vec::len)
+}
+
+/// Number of alternative words for term `j` of phrase `i`, where each
+/// alternative shares the same letters, or 0 when out of range.
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_alternatives_len(
+    results: *const AnagramResults, i: usize, j: usize,
+) -> usize {
+    results.as_ref().and_then(|r| r.anagrams.get(i)?.get(j)).map_or(0, Vec::len)
+}
+
+/// Alternative word `k` of term `j` of phrase `i`, or NULL when out of
+/// range.
+///
+/// # Safety
+///
+/// `results` must be NULL or a handle from [anagram_query].
+#[no_mangle]
+pub unsafe extern "C" fn anagram_results_word(
+    results: *const AnagramResults, i: usize, j: usize, k: usize,
+) -> *const c_char {
+    as_ptr(results.as_ref().and_then(|r| r.anagrams.get(i)?.get(j)?.get(k)))
+}
+
+/// Static description of `status`, which must not be freed.
+///
+/// Accepts any integer, since a C enum may hold codes unknown to this
+/// version, which get described as such.
+#[no_mangle]
+pub extern "C" fn anagram_status_message(status: c_int) -> *const c_char {
+    let Some(status) = AnagramStatus::from_code(status) else {
+        return c"Unknown status".as_ptr();
+    };
+    let message = match status {
+        AnagramStatus::Ok => c"OK",
+        AnagramStatus::NullArgument => c"Required argument is NULL",
+        AnagramStatus::InvalidUtf8 => c"String is not valid UTF-8",
+        AnagramStatus::Panic => c"Internal error",
+        AnagramStatus::CharOutOfBounds => {
+            c"Character is outside of expected character set range"
+        }
+        AnagramStatus::MismatchedChars => c"Reject words with chars beyond that of input",
+        AnagramStatus::NoFilePath => c"File not found",
+        AnagramStatus::PrimeTooBig => {
+            c"Prime number is larger than Big Num library can accommodate"
+        }
+        AnagramStatus::SerializationError => c"Failed while attempting to export results",
+        AnagramStatus::IoError => c"IO error",
+        AnagramStatus::WordProductNotFactor => {
+            c"Product of Primes for word not a factor of input"
+        }
+        AnagramStatus::WordProductTooBig => {
+            c"Product of Primes for word is larger than that of input"
+        }
+        AnagramStatus::WordTooLong => c"Reject words longer than input pattern",
+        AnagramStatus::LangNotImplemented => c"The requested language is not implemented",
+        AnagramStatus::CompressionNotEnabled => {
+            c"Decompressing word list requires compile-time feature"
+        }
+        AnagramStatus::RulesNotEnabled => {
+            c"Loading rules file requires compile-time feature: rules"
+        }
+        AnagramStatus::RulesFile => c"Unable to parse rules file",
+    };
+    message.as_ptr()
+}
+
+/// Prevent unwinding across FFI boundary
+fn guard(f: impl FnOnce() -> AnagramStatus) -> AnagramStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(AnagramStatus::Panic)
+}
+
+/// Borrow NUL terminated `string` as UTF-8
+unsafe fn to_str<'a>(string: *const c_char) -> Result<&'a str, AnagramStatus> {
+    CStr::from_ptr(string).to_str().map_err(|_| AnagramStatus::InvalidUtf8)
+}
+
+/// Words containing NUL would be truncated by C, so those become empty
+fn to_cstring(word: String) -> CString {
+    CString::new(word).unwrap_or_default()
+}
+
+fn as_ptr(string: Option<&CString>) -> *const c_char {
+    string.map_or(std::ptr::null(), |s| s.as_ptr())
+}
@@ -0,0 +1,60 @@
+//! Folding of letters with diacritics into their base letters, such as
+//! for treating "élan" and "elan" as the same word.
+//!
+//! Letters get decomposed per Unicode canonical decomposition (NFD)
+//! with combining marks dropped, which accommodates any script.
+//! Letters with strokes such as `ø` and `ł` lack a decomposition, so
+//! those of Latin script get folded via a table of their own.
+//! Ligatures such as `æ` and `œ` are NOT diacritics and remain as-is,
+//! and so does dotless `ı` for [Casing::Turkic] where it is a letter.
+
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::languages::Casing;
+
+/// Letters with strokes or otherwise lacking canonical decomposition,
+/// and their base letters
+const STROKES: [(char, char); 14] = [
+    ('Đ', 'D'),
+    ('đ', 'd'),
+    ('Ħ', 'H'),
+    ('ħ', 'h'),
+    ('ı', 'i'),
+    ('Ŀ', 'L'),
+    ('ŀ', 'l'),
+    ('Ł', 'L'),
+    ('ł', 'l'),
+    ('Ø', 'O'),
+    ('ø', 'o'),
+    ('Ŧ', 'T'),
+    ('ŧ', 't'),
+    // Long s is archaic rather than diacritic but equivalent nonetheless
+    ('ſ', 's'),
+];
+
+/// Base letter of `c` when it has diacritics, otherwise `c` itself,
+/// where `casing` is that of its language
+pub fn base_letter(c: char, casing: Casing) -> char {
+    let mut base = None;
+    decompose_canonical(c, |d| {
+        if base.is_none() && !is_combining_mark(d) {
+            base = Some(d);
+        }
+    });
+    stroke(base.unwrap_or(c), casing)
+}
+
+/// Replace each letter with diacritics within `word` by its base letter,
+/// where `casing` is that of its language
+pub fn diacritics(word: &str, casing: Casing) -> String {
+    word.nfd().filter(|&c| !is_combining_mark(c)).map(|c| stroke(c, casing)).collect()
+}
+
+#[inline]
+fn stroke(c: char, casing: Casing) -> char {
+    if c == 'ı' && casing == Casing::Turkic {
+        return c;
+    }
+    STROKES.iter().find(|(letter, _)| *letter == c).map_or(c, |&(_, base)| base)
+}
@@ -0,0 +1,357 @@
+//! Hunspell dictionaries, where each entry of a `.dic` file such as
+//! `word/FLAGS` expands via affix rules of its sibling `.aff` file into
+//! plurals, conjugations, etc.
+//!
+//! Only what matters for anagrams gets implemented: prefixes, suffixes,
+//! their cross products and one level of continuation classes.  Compound
+//! rules, morphology and suggestion settings get ignored.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::languages::Encoding;
+use crate::words::{self, COMPRESSED_EXTENSIONS};
+
+/// File name extension of Hunspell word list
+pub const DIC_EXTENSION: &str = "dic";
+
+/// File name extension of Hunspell affix rules
+pub const AFF_EXTENSION: &str = "aff";
+
+/// Sibling `.aff` file when `filepath` is a Hunspell `.dic` file, where
+/// either may also be compressed such as `fr.dic.gz` and `fr.aff.gz`.
+pub fn affix_file(filepath: &Path) -> Option<PathBuf> {
+    let compressed = filepath
+        .extension()
+        .is_some_and(|ext| COMPRESSED_EXTENSIONS.iter().any(|c| ext == *c));
+    let dic =
+        if compressed { filepath.with_extension("") } else { filepath.to_path_buf() };
+    if dic.extension()? != DIC_EXTENSION {
+        return None;
+    }
+    let aff = dic.with_extension(AFF_EXTENSION);
+    let candidates = COMPRESSED_EXTENSIONS
+        .iter()
+        .map(|ext| aff.with_extension(format!("{AFF_EXTENSION}.{ext}")));
+    std::iter::once(aff.clone()).chain(candidates).find(|path| path.is_file())
+}
+
+/// Expand every entry of `dic` file via rules of `aff` file, where
+/// `encoding` applies unless overridden by `SET` within `aff`.
+///
+/// Returns sorted list of unique words.
+pub fn expand(dic: &Path, aff: &Path, encoding: &Encoding) -> Result<Vec<String>> {
+    expand_from(words::open_file(dic)?, words::open_file(aff)?, encoding)
+}
+
+/// Equivalent of [expand] but reading from `dic` and `aff` readers.
+pub fn expand_from<D: BufRead, A: BufRead>(
+    dic: D, aff: A, encoding: &Encoding,
+) -> Result<Vec<String>> {
+    let affixes = Affixes::parse(aff, encoding)?;
+    let mut words = vec![];
+    for (i, line) in lines(dic, &affixes.encoding)?.iter().enumerate() {
+        // First line is approximate count of entries
+        if i == 0 && line.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        words.extend(affixes.expand_entry(line));
+    }
+    words.sort_unstable();
+    words.dedup();
+    Ok(words)
+}
+
+/// How flags get encoded within `.dic` entries and affix rules
+#[derive(Debug, Default, PartialEq)]
+enum FlagType {
+    /// One ASCII char per flag
+    #[default]
+    Short,
+    /// Two chars per flag
+    Long,
+    /// Decimal numbers separated by commas
+    Numeric,
+    /// One Unicode char per flag
+    Utf8,
+}
+
+/// One line of `PFX` or `SFX` group
+#[derive(Debug)]
+struct Rule {
+    strip: String,
+    add: String,
+    /// Continuation classes applying to result of this rule
+    flags: Vec<String>,
+    condition: Vec<CharClass>,
+}
+
+/// Element of a rule's condition, which resembles a regular expression
+#[derive(Debug)]
+enum CharClass {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+/// Rules sharing one flag
+#[derive(Debug, Default)]
+struct Group {
+    cross_product: bool,
+    rules: Vec<Rule>,
+}
+
+/// Affix rules parsed from `.aff` file
+#[derive(Debug, Default)]
+pub struct Affixes {
+    encoding: Encoding,
+    flag_type: FlagType,
+    /// Flag aliases via `AF`, where `.dic` entries refer to index from 1
+    aliases: Vec<String>,
+    prefixes: HashMap<String, Group>,
+    suffixes: HashMap<String, Group>,
+    /// Flags of entries that must not appear without an affix
+    need_affix: Vec<String>,
+    /// Flags of entries dropped entirely along with all their affixed
+    /// forms, such as misspellings or offensive words
+    forbidden: Vec<String>,
+}
+
+impl Affixes {
+    /// Parse `.aff` file contents
+    pub fn parse<R: BufRead>(reader: R, encoding: &Encoding) -> Result<Affixes> {
+        let mut bytes = vec![];
+        let mut reader = reader;
+        reader.read_to_end(&mut bytes)?;
+        // Encoding must be known prior to decoding remainder
+        let encoding = match set_encoding(&bytes) {
+            Some(encoding) => encoding,
+            None => encoding.clone(),
+        };
+        let mut affixes = Affixes { encoding, ..Affixes::default() };
+        let mut aliases_count = None;
+        for line in lines(&bytes[..], &affixes.encoding)? {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                ["FLAG", flag_type, ..] => {
+                    affixes.flag_type = match flag_type {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Numeric,
+                        "UTF-8" => FlagType::Utf8,
+                        _ => FlagType::Short,
+                    }
+                }
+                // Header is count of aliases that follow
+                ["AF", count, ..] if aliases_count.is_none() => {
+                    aliases_count = Some(count.parse::<usize>().unwrap_or_default())
+                }
+                ["AF", flags, ..] => affixes.aliases.push(flags.to_string()),
+                ["NEEDAFFIX" | "ONLYINCOMPOUND", flag, ..] => {
+                    affixes.need_affix.push(flag.to_string())
+                }
+                ["FORBIDDENWORD", flag, ..] => affixes.forbidden.push(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, strip, add, ..] => {
+                    let groups = match kind {
+                        "PFX" => &mut affixes.prefixes,
+                        _ => &mut affixes.suffixes,
+                    };
+                    match groups.get_mut(flag) {
+                        // Header: flag, cross product, count
+                        None => {
+                            let group =
+                                Group { cross_product: strip == "Y", rules: vec![] };
+                            groups.insert(flag.to_string(), group);
+                        }
+                        Some(group) => {
+                            let (add, flags) = add.split_once('/').unwrap_or((add, ""));
+                            let condition = fields.get(4).copied().unwrap_or(".");
+                            group.rules.push(Rule {
+                                strip: zero_as_empty(strip),
+                                add: zero_as_empty(add),
+                                flags: parse_flags(
+                                    &affixes.flag_type,
+                                    &affixes.aliases,
+                                    flags,
+                                ),
+                                condition: parse_condition(condition),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Like Hunspell, only as many aliases as declared by the header
+        if let Some(count) = aliases_count.filter(|&n| n != affixes.aliases.len()) {
+            eprintln!(
+                "Affix file: AF declares {count} aliases but has {}",
+                affixes.aliases.len()
+            );
+            affixes.aliases.truncate(count);
+        }
+        Ok(affixes)
+    }
+
+    /// Expand one line of `.dic` file such as `word/FLAGS` into the word
+    /// itself and every form generated by its affix flags.
+    pub fn expand_entry(&self, line: &str) -> Vec<String> {
+        // Morphological fields follow after whitespace
+        let entry = line.split_whitespace().next().unwrap_or_default();
+        let (root, flags) = entry.split_once('/').unwrap_or((entry, ""));
+        if root.is_empty() {
+            return vec![];
+        }
+        let flags = parse_flags(&self.flag_type, &self.aliases, flags);
+        if flags.iter().any(|f| self.forbidden.contains(f)) {
+            return vec![];
+        }
+        let mut words = vec![];
+        if !flags.iter().any(|f| self.need_affix.contains(f)) {
+            words.push(root.to_string());
+        }
+        let mut cross = vec![];
+        for group in flags.iter().filter_map(|f| self.suffixes.get(f)) {
+            for rule in &group.rules {
+                if rule.flags.iter().any(|f| self.forbidden.contains(f)) {
+                    continue;
+                }
+                let Some(word) = rule.suffix(root) else { continue };
+                for continued in rule.flags.iter().filter_map(|f| self.suffixes.get(f)) {
+                    words.extend(continued.rules.iter().filter_map(|r| r.suffix(&word)));
+                }
+                if group.cross_product {
+                    cross.push(word.clone());
+                }
+                words.push(word);
+            }
+        }
+        for group in flags.iter().filter_map(|f| self.prefixes.get(f)) {
+            for rule in &group.rules {
+                if rule.flags.iter().any(|f| self.forbidden.contains(f)) {
+                    continue;
+                }
+                words.extend(rule.prefix(root));
+                if group.cross_product {
+                    words.extend(cross.iter().filter_map(|w| rule.prefix(w)));
+                }
+            }
+        }
+        words
+    }
+}
+
+impl Rule {
+    /// Apply as suffix to `word` if its condition matches
+    fn suffix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = self.condition.len();
+        if n > chars.len() || !matches(&self.condition, &chars[chars.len() - n..]) {
+            return None;
+        }
+        let stem = word.strip_suffix(self.strip.as_str())?;
+        let word = format!("{stem}{}", self.add);
+        (!word.is_empty()).then_some(word)
+    }
+
+    /// Apply as prefix to `word` if its condition matches
+    fn prefix(&self, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = self.condition.len();
+        if n > chars.len() || !matches(&self.condition, &chars[..n]) {
+            return None;
+        }
+        let stem = word.strip_prefix(self.strip.as_str())?;
+        let word = format!("{}{stem}", self.add);
+        (!word.is_empty()).then_some(word)
+    }
+}
+
+fn matches(condition: &[CharClass], chars: &[char]) -> bool {
+    condition.iter().zip(chars).all(|(class, c)| match class {
+        CharClass::Any => true,
+        CharClass::OneOf(set) => set.contains(c),
+        CharClass::NoneOf(set) => !set.contains(c),
+    })
+}
+
+/// Parse condition such as `[^aeiou]y`, where `.` matches any char
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+    let mut classes = vec![];
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => classes.push(CharClass::Any),
+            '[' => {
+                let set: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                match set.strip_prefix('^') {
+                    Some(set) => classes.push(CharClass::NoneOf(set.chars().collect())),
+                    None => classes.push(CharClass::OneOf(set.chars().collect())),
+                }
+            }
+            c => classes.push(CharClass::OneOf(vec![c])),
+        }
+    }
+    classes
+}
+
+/// Split `flags` per `flag_type`, resolving `AF` aliases
+fn parse_flags(flag_type: &FlagType, aliases: &[String], flags: &str) -> Vec<String> {
+    let flags = match flags.parse::<usize>() {
+        Ok(i) if !aliases.is_empty() => aliases.get(i.wrapping_sub(1)).map_or("", |f| f),
+        _ => flags,
+    };
+    match flag_type {
+        FlagType::Short | FlagType::Utf8 => flags.chars().map(String::from).collect(),
+        FlagType::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+        }
+        FlagType::Numeric => {
+            flags.split(',').filter(|f| !f.is_empty()).map(String::from).collect()
+        }
+    }
+}
+
+fn zero_as_empty(affix: &str) -> String {
+    if affix == "0" {
+        String::new()
+    } else {
+        affix.to_string()
+    }
+}
+
+/// Value of `SET` within `.aff` file, which is always ASCII
+fn set_encoding(bytes: &[u8]) -> Option<Encoding> {
+    let line = bytes.split(|&b| b == b'\n').find(|line| line.starts_with(b"SET "))?;
+    let set = String::from_utf8_lossy(&line[4..]).trim().to_uppercase();
+    match set.as_str() {
+        "UTF-8" => Some(Encoding::Utf_8),
+        "ISO8859-1" | "ISO-8859-1" => Some(Encoding::Iso_8859_1),
+        _ => None,
+    }
+}
+
+/// Decode each line of `reader` per `encoding`, trimmed and skipping
+/// blank lines and comments
+fn lines<R: BufRead>(mut reader: R, encoding: &Encoding) -> Result<Vec<String>> {
+    let mut lines = vec![];
+    let mut bytes = vec![];
+    loop {
+        bytes.clear();
+        if reader.read_until(b'\n', &mut bytes)? == 0 {
+            break;
+        }
+        let line: String = if *encoding == Encoding::Iso_8859_1 {
+            bytes.iter().map(|&x| char::from(x)).collect()
+        } else {
+            String::from_utf8_lossy(&bytes).to_string()
+        };
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            lines.push(line.to_string());
+        }
+    }
+    Ok(lines)
+}
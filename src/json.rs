@@ -1,12 +1,15 @@
-//! Write results as JSON file.
+//! Write results as JSON file, and read them back.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 
 use crate::error::Result;
+use crate::search::{self, Solution};
+use crate::sources::Sources;
 
 /// Organization within JSON file.
 ///
@@ -14,22 +17,93 @@ use crate::error::Result;
 /// ("anagrams").
 #[derive(Serialize)]
 struct JsonExport<'a, 'b> {
+    /// Query terms when exporting results of many queries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<&'a str>,
     /// "Transpositions" are results strictly consisting of single words.
     transpositions: &'a [String],
     /// "Anagrams" are results strictly consisting of multiple words.
     anagrams: &'b [Vec<Vec<String>>],
+    /// Tag of word list from which each word came, when more than one
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    sources: BTreeMap<String, String>,
+}
+
+/// Results previously exported by [write] when read back by [read].
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct Results {
+    /// "Transpositions" are results strictly consisting of single words.
+    pub transpositions: Vec<String>,
+    /// "Anagrams" are results strictly consisting of multiple words.
+    pub anagrams: Vec<Vec<Vec<String>>>,
+    /// Tag of word list from which each word came, if exported
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
 }
 
 /// Persist transpositions and anagrams as JSON file.
+///
+/// See also: fn [write_to].
 pub fn write(
     filepath: &PathBuf, max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
 ) -> Result<()> {
-    let mut f = File::create(filepath)?;
+    write_to(File::create(filepath)?, max, singles, phrases)
+}
+
+/// Write transpositions and anagrams as JSON to any `writer` such as
+/// [std::io::Stdout], an in-memory buffer or a compressed stream.
+pub fn write_to<W: Write>(
+    writer: W, max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
+) -> Result<()> {
+    write_tagged_to(writer, max, singles, phrases, &Sources::default())
+}
+
+/// Same as [write_to] but adding `sources` with the tag of each word
+/// when loaded from more than one tagged word list.
+pub fn write_tagged_to<W: Write>(
+    mut writer: W, max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
+    sources: &Sources,
+) -> Result<()> {
+    let anagrams = by_word_count(max, phrases);
+    let export = JsonExport {
+        query: None,
+        transpositions: singles,
+        anagrams: &anagrams,
+        sources: sources.provenance(singles, phrases),
+    };
+    serde_json::to_writer(&mut writer, &export)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write results of many queries as JSON array to any `writer`, where
+/// each element is labelled by its `query`.
+pub fn write_batch_to<W: Write>(
+    mut writer: W, max: usize, solutions: &[Solution],
+) -> Result<()> {
+    let anagrams: Vec<Vec<Vec<Vec<String>>>> =
+        solutions.iter().map(|s| by_word_count(max, &s.anagrams)).collect();
+    let export: Vec<JsonExport> = solutions
+        .iter()
+        .zip(anagrams.iter())
+        .map(|(s, anagrams)| JsonExport {
+            query: Some(&s.query),
+            transpositions: &s.transpositions,
+            anagrams,
+            sources: BTreeMap::new(),
+        })
+        .collect();
+    serde_json::to_writer(&mut writer, &export)?;
+    writer.flush()?;
+    Ok(())
+}
 
+/// Sort `phrases` by number of words, including any longer than `max`
+fn by_word_count(max: usize, phrases: &[Vec<Vec<String>>]) -> Vec<Vec<Vec<String>>> {
     let mut anagrams: Vec<Vec<Vec<String>>> = Vec::with_capacity(phrases.len());
     let limit = phrases.len();
     let mut count = 0;
-    for n in 2..=max {
+    for n in 2..=search::max_word_count(max, phrases) {
         for terms in phrases {
             if terms.len() == n {
                 anagrams.push(terms.clone());
@@ -40,8 +114,17 @@ pub fn write(
             break;
         }
     }
+    anagrams
+}
 
-    let export = JsonExport { transpositions: singles, anagrams: &anagrams };
-    f.write_all(serde_json::to_string(&export)?.as_bytes())?;
-    Ok(())
+/// Load transpositions and anagrams from JSON file created by [write].
+///
+/// See also: fn [read_from] and [crate::diff::compare].
+pub fn read(filepath: &Path) -> Result<Results> {
+    read_from(BufReader::new(File::open(filepath)?))
+}
+
+/// Load transpositions and anagrams as JSON from any `reader`.
+pub fn read_from<R: Read>(reader: R) -> Result<Results> {
+    Ok(serde_json::from_reader(reader)?)
 }
@@ -9,41 +9,137 @@
 //! Some single-letter words are "a" and "I", but those are specific
 //! to English.  Spanish uses "y" as a conjunction.  And so on.
 //!
+//! Likewise, German capitalizes every noun, so words starting with
+//! uppercase aren't indicative of proper names there.
+//!
 //! This module provides relatively simple mappings for reasonable
-//! defaults for each language supported.
+//! defaults for each language supported.  These may be overridden or
+//! extended per language and region via [Rules] loaded from a file.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::convert::From;
+use std::path::Path;
 use std::sync::LazyLock;
 
-use crate::error::AnagramError;
+use crate::error::{AnagramError, Result};
+use crate::primes::Alphabet;
 
 /// Languages currently supported to varying degrees... Pull requests welcome
-#[derive(Deserialize, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(
+    Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord,
+)]
 pub enum Language {
     #[default]
     Any,
-    // Only necessary if something has been added to `UPCASE` or `SHORT`.
+    // Only necessary if something has been added to `UPCASE` or `SHORT`,
+    // or if using a script other than Latin.
     // Please keep this list sorted alphabetically.
+    DE, // German, Deutsch; Latin-1
+    EL, // Greek, Ελληνικά; Greek
     EN, // English; Latin-1
     ES, // Spanish, Español; Latin-1
     FR, // French, Français; Latin-1
+    IT, // Italian, Italiano; Latin-1
+    NL, // Dutch, Nederlands; Latin-1
+    PT, // Portuguese, Português; Latin-1
+    RU, // Russian, Русский; Cyrillic
+    TR, // Turkish, Türkçe; Latin-1 plus ğ, ı, ş
+    UK, // Ukrainian, Українська; Cyrillic (unrelated to Region::UK)
+}
+
+/// Writing system of a [Language], selecting which letters get the
+/// smallest primes; see fn [crate::primes::hash].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Script {
+    #[default]
+    Latin,
+    Cyrillic,
+    Greek,
+}
+
+impl Language {
+    /// Script in which words of this language are written
+    pub fn script(&self) -> Script {
+        match self {
+            Language::EL => Script::Greek,
+            Language::RU | Language::UK => Script::Cyrillic,
+            _ => Script::Latin,
+        }
+    }
+
+    /// Lowercasing of words in this language
+    pub fn casing(&self) -> Casing {
+        match self {
+            Language::EL => Casing::Greek,
+            Language::TR => Casing::Turkic,
+            _ => Casing::Unicode,
+        }
+    }
+
+    /// Letters counted as multiple letters within anagrams; see [EXPANSIONS]
+    pub fn expansions(&self) -> &'static [(char, &'static str)] {
+        EXPANSIONS.get(self).map_or(&[], |v| v.as_slice())
+    }
+}
+
+/// Lowercasing per [Language] where letters get counted differently
+/// than via [str::to_lowercase], which is locale-agnostic.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Casing {
+    /// Same as [str::to_lowercase]
+    #[default]
+    Unicode,
+    /// Turkish and Azerbaijani: I lowercases to dotless ı, and dotted İ
+    /// to i, rather than i followed by combining dot
+    Turkic,
+    /// Greek: final sigma ς counts as σ, since its position within
+    /// words of an anagram may differ
+    Greek,
+}
+
+impl Casing {
+    /// Lowercase `text` per this casing
+    pub fn lowercase(&self, text: &str) -> String {
+        match self {
+            Casing::Unicode => text.to_lowercase(),
+            Casing::Turkic => text
+                .chars()
+                .map(|ch| match ch {
+                    'I' => 'ı',
+                    'İ' => 'i',
+                    ch => ch,
+                })
+                .collect::<String>()
+                .to_lowercase(),
+            Casing::Greek => text.to_lowercase().replace('ς', "σ"),
+        }
+    }
 }
 
 /// Region portion of "LANG" environment variable.
 ///
 /// Complements [Language] such that its `EN` for English may be
-/// further distinguished between UK, US, Canada, etc.  This
+/// further distinguished between UK, US, Canada, etc., and likewise
+/// `PT` for Portuguese between Brazil and Portugal, `DE` for German
+/// between Austria, Switzerland and Germany, etc.  This
 /// corresponds to the second component of LANG environment variable;
 /// e.g., in Bash, `export LANG="en-US"`
-#[derive(Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
 pub enum Region {
     #[default]
     Any,
     // Please keep this alphabetized:
+    AT,
+    BE,
+    BR,
     CA,
+    CH,
+    DE,
     GB, // official but some use UK
+    IT,
+    NL,
+    PT,
     UK, // unofficial but often preferred to GB
     US,
 }
@@ -56,6 +152,20 @@ pub enum Encoding {
     Iso_8859_1,
 }
 
+/// Unicode canonical form applied to query and dictionary words, such
+/// that "é" as U+00E9 and "e" followed by combining U+0301 are equal.
+///
+/// Either way, letters get hashed as composed, so accents remain
+/// significant unless folded via [crate::primes::Alphabet::fold_diacritics].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Normalization {
+    /// Canonical composition, such as U+00E9 for "é"
+    #[default]
+    NFC,
+    /// Canonical decomposition, such as U+0065 U+0301 for "é"
+    NFD,
+}
+
 /// Words allowed to start with uppercase.
 ///
 /// Associate what words are acceptable when otherwise bypassing
@@ -84,21 +194,232 @@ pub static UPCASE: LazyLock<BTreeMap<Language, Vec<&'static str>>> =
 pub static SHORT: LazyLock<BTreeMap<Language, Vec<&'static str>>> = LazyLock::new(|| {
     use Language::*;
     let mut tree = BTreeMap::new();
+    tree.insert(EL, vec!["ή", "η", "ο", "ω"]);
     tree.insert(EN, vec!["I", "a"]);
     tree.insert(ES, vec!["a", "y"]);
     tree.insert(FR, vec!["a", "d", "i", "j", "l", "m", "s", "t", "y"]);
+    tree.insert(IT, vec!["a", "e", "i", "o"]);
+    tree.insert(NL, vec!["u"]);
+    tree.insert(PT, vec!["a", "e", "o"]);
+    tree.insert(RU, vec!["а", "в", "и", "к", "о", "с", "у", "я"]);
+    tree.insert(TR, vec!["o"]);
+    tree.insert(UK, vec!["а", "в", "з", "й", "і", "о", "у", "я"]);
     tree
 });
 
+/// Lowercase letters counted as multiple letters within anagrams.
+///
+/// Languages differ on whether ligatures and the like are letters of
+/// their own.  For instance, "Straße" and "Strasse" resolve the same
+/// way for German, and "cœur" uses "o" and "e" for French.  Letters
+/// not listed for a language remain distinct letters.
+pub static EXPANSIONS: LazyLock<BTreeMap<Language, Vec<(char, &'static str)>>> =
+    LazyLock::new(|| {
+        use Language::*;
+        let mut tree = BTreeMap::new();
+        tree.insert(DE, vec![('ß', "ss")]);
+        tree.insert(EN, vec![('æ', "ae"), ('œ', "oe")]);
+        tree.insert(FR, vec![('æ', "ae"), ('œ', "oe")]);
+        tree.insert(NL, vec![('ĳ', "ij")]);
+        tree
+    });
+
+/// Languages capitalizing every noun, such as German.
+///
+/// Words starting with uppercase are never rejected for these, since
+/// doing so would discard most nouns along with proper names.
+pub static CAPITALIZED_NOUNS: &[Language] = &[Language::DE];
+
+/// Languages without any single letter words, such as German.
+///
+/// These get a `min_length` of 2 within [Rules::builtin], since an
+/// empty list within [SHORT] would otherwise allow every single letter.
+pub static WITHOUT_SHORT: &[Language] = &[Language::DE];
+
+/// Filter rules for one language and region, beginning with built-in
+/// defaults of [SHORT] and [UPCASE], optionally overridden per file.
+///
+/// A rules file in TOML (or JSON when named `*.json`) contains a table
+/// per language such as `EN` and optionally per region such as `EN_GB`,
+/// each with any of the fields below.  Each field specified for the
+/// region replaces that of its language, which replaces the built-in:
+///
+/// ```toml
+/// [EN]
+/// min_length = 2
+/// stopwords = ["th", "st"]
+///
+/// [EN_GB]
+/// upcase = ["I", "Thames"]
+/// ```
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Rules {
+    pub lang: Language,
+    pub region: Region,
+    /// Single letter words allowed; see [SHORT]
+    pub short: Vec<String>,
+    /// Words allowed to start with uppercase; see [UPCASE]
+    pub upcase: Vec<String>,
+    /// Allow every word starting with uppercase; see [CAPITALIZED_NOUNS]
+    pub capitalized_nouns: bool,
+    /// Reject words with fewer letters than this, except those within
+    /// `short`.  Zero disables.
+    pub min_length: usize,
+    /// Words always rejected, matched exactly
+    pub stopwords: Vec<String>,
+    /// Whether words get hashed ignoring diacritics; set by
+    /// [crate::config::Config::rules] rather than rules files.
+    pub fold_diacritics: bool,
+    /// Canonical form of words; likewise set by `Config::rules`.
+    #[serde(skip)]
+    pub normalization: Normalization,
+}
+
+/// Fields of a table within rules file, where each is optional
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Overlay {
+    short: Option<Vec<String>>,
+    upcase: Option<Vec<String>>,
+    capitalized_nouns: Option<bool>,
+    min_length: Option<usize>,
+    stopwords: Option<Vec<String>>,
+}
+
+impl Rules {
+    /// Built-in rules for `lang` from [SHORT], [UPCASE],
+    /// [CAPITALIZED_NOUNS] and [WITHOUT_SHORT]
+    pub fn builtin(lang: &Language) -> Rules {
+        let words = |table: &BTreeMap<Language, Vec<&str>>| {
+            table.get(lang).map_or(vec![], |v| v.iter().map(|w| w.to_string()).collect())
+        };
+        Rules {
+            lang: lang.clone(),
+            short: words(&SHORT),
+            upcase: words(&UPCASE),
+            capitalized_nouns: CAPITALIZED_NOUNS.contains(lang),
+            min_length: if WITHOUT_SHORT.contains(lang) { 2 } else { 0 },
+            ..Rules::default()
+        }
+    }
+
+    /// Built-in rules for `lang` overridden by those of `lang` and then
+    /// `region` within `filepath`, when specified.  Tables for other
+    /// languages and regions are ignored.
+    ///
+    /// Parsing requires compile-time feature `rules`, otherwise
+    /// [AnagramError::RulesNotEnabled].
+    pub fn load(
+        filepath: Option<&Path>, lang: &Language, region: &Region,
+    ) -> Result<Rules> {
+        // UK is ISO-unofficial, and rules files get parsed per [parse_lang]
+        let region = match region {
+            Region::UK => Region::GB,
+            region => region.clone(),
+        };
+        let mut rules = Rules { region: region.clone(), ..Rules::builtin(lang) };
+        let Some(filepath) = filepath else {
+            return Ok(rules);
+        };
+        let text = std::fs::read_to_string(filepath)?;
+        let is_json = filepath.extension().is_some_and(|ext| ext == "json");
+        let tables = parse_tables(&text, is_json)?;
+        let (mut language, mut regional) = (None, None);
+        for (key, overlay) in tables {
+            let (l, r) = parse_lang(&key);
+            // Unknown languages parse as `Any`, which only applies when explicit
+            if l != *lang || (l == Language::Any && !key.eq_ignore_ascii_case("any")) {
+                continue;
+            }
+            if key.len() <= 3 {
+                language = Some(overlay);
+            } else if r == region && r != Region::Any {
+                regional = Some(overlay);
+            }
+        }
+        for overlay in [language, regional].into_iter().flatten() {
+            rules.apply(overlay);
+        }
+        Ok(rules)
+    }
+
+    /// Mapping of letters to primes for words of `lang`
+    pub fn alphabet(&self) -> Alphabet {
+        Alphabet {
+            script: self.lang.script(),
+            expansions: self.lang.expansions(),
+            casing: self.lang.casing(),
+            fold_diacritics: self.fold_diacritics,
+            normalization: self.normalization,
+        }
+    }
+
+    fn apply(&mut self, overlay: Overlay) {
+        if let Some(short) = overlay.short {
+            self.short = short;
+        }
+        if let Some(upcase) = overlay.upcase {
+            self.upcase = upcase;
+        }
+        if let Some(capitalized_nouns) = overlay.capitalized_nouns {
+            self.capitalized_nouns = capitalized_nouns;
+        }
+        if let Some(min_length) = overlay.min_length {
+            self.min_length = min_length;
+        }
+        if let Some(stopwords) = overlay.stopwords {
+            self.stopwords = stopwords;
+        }
+    }
+
+    /// Same as fn [filter] but also applying `min_length` and
+    /// `stopwords`, and ignoring `upcase` and `skip_upcase` when
+    /// `capitalized_nouns`.  Return value indicates whether to reject
+    /// `word`.
+    pub fn filter(&self, word: &str, skip_short: bool, skip_upcase: bool) -> bool {
+        let (upcase, skip_upcase) = match self.capitalized_nouns {
+            true => (&[][..], false),
+            false => (&self.upcase[..], skip_upcase),
+        };
+        filter(word, &self.short, upcase, skip_short, skip_upcase)
+            || (self.min_length > 0
+                && word.chars().filter(|c| c.is_alphabetic()).count() < self.min_length
+                && !self.short.iter().any(|w| w == word))
+            || self.stopwords.iter().any(|w| w == word)
+    }
+}
+
+#[cfg(feature = "rules")]
+fn parse_tables(text: &str, is_json: bool) -> Result<BTreeMap<String, Overlay>> {
+    if is_json {
+        serde_json::from_str(text).map_err(|e| AnagramError::RulesFile(e.to_string()))
+    } else {
+        toml::from_str(text).map_err(|e| AnagramError::RulesFile(e.to_string()))
+    }
+}
+
+#[cfg(not(feature = "rules"))]
+fn parse_tables(_text: &str, _is_json: bool) -> Result<BTreeMap<String, Overlay>> {
+    Err(AnagramError::RulesNotEnabled)
+}
+
 impl std::str::FromStr for Language {
     type Err = AnagramError;
 
-    fn from_str(string: &str) -> Result<Self, Self::Err> {
+    fn from_str(string: &str) -> std::result::Result<Self, Self::Err> {
         match string.to_uppercase().as_str() {
             "ANY" => Ok(Language::Any),
+            "DE" => Ok(Language::DE),
+            "EL" => Ok(Language::EL),
             "EN" => Ok(Language::EN),
             "ES" => Ok(Language::ES),
             "FR" => Ok(Language::FR),
+            "IT" => Ok(Language::IT),
+            "NL" => Ok(Language::NL),
+            "PT" => Ok(Language::PT),
+            "RU" => Ok(Language::RU),
+            "TR" => Ok(Language::TR),
+            "UK" => Ok(Language::UK),
             _ => Err(AnagramError::LangNotImplemented),
         }
     }
@@ -107,9 +428,17 @@ impl std::str::FromStr for Language {
 impl From<&str> for Language {
     fn from(string: &str) -> Language {
         match string.to_uppercase().as_str() {
+            "DE" => Language::DE,
+            "EL" => Language::EL,
             "EN" => Language::EN,
             "ES" => Language::ES,
             "FR" => Language::FR,
+            "IT" => Language::IT,
+            "NL" => Language::NL,
+            "PT" => Language::PT,
+            "RU" => Language::RU,
+            "TR" => Language::TR,
+            "UK" => Language::UK,
             _ => Language::Any,
         }
     }
@@ -118,8 +447,16 @@ impl From<&str> for Language {
 impl From<&str> for Region {
     fn from(string: &str) -> Region {
         match string.to_uppercase().as_str() {
+            "AT" => Region::AT,
+            "BE" => Region::BE,
+            "BR" => Region::BR,
             "CA" => Region::CA,
+            "CH" => Region::CH,
+            "DE" => Region::DE,
             "GB" => Region::GB,
+            "IT" => Region::IT,
+            "NL" => Region::NL,
+            "PT" => Region::PT,
             "UK" => Region::GB, // UK is ISO-unofficial but regionally correct
             "US" => Region::US,
             _ => Region::Any,
@@ -134,17 +471,17 @@ impl From<&str> for Region {
 /// Boolean parameters take precedence over supplied word lists.
 /// Return value indicates whether to reject dictionary `word` or not.
 #[inline]
-pub fn filter(
-    word: &str, short_words: &[&str], upcase_words: &[&str], skip_short: bool,
+pub fn filter<S: AsRef<str>>(
+    word: &str, short_words: &[S], upcase_words: &[S], skip_short: bool,
     skip_upcase: bool,
 ) -> bool {
-    if word.len() == 1 {
+    if word.chars().count() == 1 {
         if skip_short {
             true
         } else if short_words.is_empty() {
             false
         } else {
-            !short_words.iter().any(|&w| w == word)
+            !short_words.iter().any(|w| w.as_ref() == word)
         }
     } else if let Some(ch) = word.chars().next() {
         if ch.is_uppercase() {
@@ -153,7 +490,7 @@ pub fn filter(
             } else if upcase_words.is_empty() {
                 false
             } else {
-                !upcase_words.iter().any(|&w| w == word)
+                !upcase_words.iter().any(|w| w.as_ref() == word)
             }
         } else {
             false
@@ -5,19 +5,54 @@ extern crate num_traits;
 #[cfg(feature = "external-hasher")]
 extern crate char_seq;
 
+pub mod blocklist;
 pub mod config;
 #[cfg(feature = "cli")]
 pub mod csv;
+pub mod diff;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fold;
+pub mod hunspell;
 #[cfg(feature = "cli")]
 pub mod json;
 pub mod languages;
 pub mod primes;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod search;
+pub mod sources;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(test)]
+mod test_blocklist;
+#[cfg(all(test, feature = "cli"))]
+mod test_csv;
+#[cfg(test)]
+mod test_diff;
+#[cfg(all(test, feature = "ffi"))]
+mod test_ffi;
+#[cfg(test)]
+mod test_helpers;
+#[cfg(test)]
+mod test_hunspell;
+#[cfg(all(test, feature = "cli"))]
+mod test_json;
 #[cfg(test)]
 mod test_languages;
 #[cfg(test)]
 mod test_primes;
+#[cfg(all(test, feature = "python"))]
+mod test_python;
 #[cfg(test)]
 mod test_search;
+#[cfg(test)]
+mod test_sources;
+#[cfg(all(test, feature = "sqlite"))]
+mod test_sqlite;
+#[cfg(test)]
+mod test_words;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod words;
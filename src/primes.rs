@@ -1,13 +1,19 @@
 use num_bigint::BigUint;
 use num_bigint::ToBigUint;
 use num_traits::One;
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::ops::Rem;
+use unicode_normalization::{
+    is_nfc_quick, is_nfd_quick, IsNormalized, UnicodeNormalization,
+};
 
 #[cfg(feature = "external-hasher")]
 use char_seq;
 
 use crate::error::{AnagramError, Result};
+use crate::fold;
+use crate::languages::{Casing, Normalization, Script};
 
 /// Product of primes associated with words in phrase
 /// used for intermediate and final results.
@@ -23,8 +29,10 @@ pub type PMap = BTreeMap<BigUint, Vec<String>>;
 /// isolate hasher results by natural language; e.g., isolate English
 /// from Français yet may be mixed for fr_CA and fr_FR, but
 /// dictionaries would differ.)
+///
+/// Letters of the [Script] of each language get the smallest primes,
+/// followed by those of Latin script; see fn [hash].
 #[rustfmt::skip]
-// TODO: per-LANG mapping from UTF-8 to contiguous sequence of primes.
 //   a,b,c,d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z
 const PRIMES: [u16; 200] =
     [2,3,5,7,11,13,17,19,23,29,31,37,41,43,47,53,59,61,67,71,73,79,83,89,97,101,
@@ -34,7 +42,10 @@ const PRIMES: [u16; 200] =
      191,193,197,199,211,223,227,229,233,239,241,251,257,263,269,271,
      // Expand to include all ISO-8859-* and ranges from UTF-8.
      // Accommodating both modern and historical scripts within a single
-     // use case, such as contemporary Cyrillic quoting an ancient passage:
+     // use case, such as contemporary Cyrillic quoting an ancient passage.
+     // Latin: 0..26 ASCII, 26..121 U+00A1..U+00FF, then LATIN_EXTENDED.
+     // Cyrillic: 0..49 for а..џ plus ґ, then Latin offset by 49.
+     // Greek: 0..36 for ά..ώ plus ΐ, then Latin offset by 36.
      277,281,283,293,307,311,313,317,331,337,347,349,353,359,367,373,
      379,383,389,397,401,409,419,421,431,433,439,443,449,457,461,463,
      467,479,487,491,499,503,509,521,523,541,547,557,563,569,571,577,
@@ -46,6 +57,90 @@ const PRIMES: [u16; 200] =
      1097,1103,1109,1117,1123,1129,1151,1153,1163,1171,1181,1187,1193,
      1201,1213,1217,1223];
 
+/// Mapping of letters to primes for one natural language, applied
+/// identically to query and dictionary words.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Alphabet {
+    /// Letters of this script get the smallest primes; see fn [hash]
+    pub script: Script,
+    /// Letters replaced by multiple letters, such as "ß" by "ss" for
+    /// German; see [crate::languages::EXPANSIONS].
+    pub expansions: &'static [(char, &'static str)],
+    /// Lowercase per language, such as Turkish dotless ı
+    pub casing: Casing,
+    /// Map letters with diacritics to their base letters such that
+    /// "vérité" gets the same primes as "verite"; see [fold].
+    pub fold_diacritics: bool,
+    /// Canonical form of words retained within results, while letters
+    /// get hashed as composed (NFC) either way
+    pub normalization: Normalization,
+}
+
+impl Alphabet {
+    /// Equivalent of lowercase `ch` for hashing
+    #[inline]
+    pub fn letter(&self, ch: char) -> char {
+        match self.fold_diacritics {
+            true => fold::base_letter(ch, self.casing),
+            false => ch,
+        }
+    }
+
+    /// Lowercase `word` with each letter of `expansions` replaced,
+    /// borrowed when there are none
+    pub fn expand<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        if !word.chars().any(|ch| self.expansion(ch).is_some()) {
+            return Cow::Borrowed(word);
+        }
+        let mut expanded = String::with_capacity(word.len() + 1);
+        for ch in word.chars() {
+            match self.expansion(ch) {
+                Some(letters) => expanded.push_str(letters),
+                None => expanded.push(ch),
+            }
+        }
+        Cow::Owned(expanded)
+    }
+
+    #[inline]
+    fn expansion(&self, ch: char) -> Option<&'static str> {
+        self.expansions.iter().find(|(c, _)| *c == ch).map(|(_, letters)| *letters)
+    }
+
+    /// `word` in canonical form per `normalization`, borrowed when
+    /// already in that form
+    pub fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        match self.normalization {
+            Normalization::NFC if is_nfc_quick(word.chars()) == IsNormalized::Yes => {
+                Cow::Borrowed(word)
+            }
+            Normalization::NFD if is_nfd_quick(word.chars()) == IsNormalized::Yes => {
+                Cow::Borrowed(word)
+            }
+            Normalization::NFC => Cow::Owned(word.nfc().collect()),
+            Normalization::NFD => Cow::Owned(word.nfd().collect()),
+        }
+    }
+
+    /// `word` with each letter and its combining marks composed (NFC)
+    /// for hashing regardless of `normalization`, such that "é" remains
+    /// distinct from "e" even when words get retained as NFD
+    fn compose<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        match is_nfc_quick(word.chars()) {
+            IsNormalized::Yes => Cow::Borrowed(word),
+            _ => Cow::Owned(word.nfc().collect()),
+        }
+    }
+
+    /// Same as [Alphabet::normalize] but for `word` already owned
+    pub fn normalized(&self, word: String) -> String {
+        match self.normalize(&word) {
+            Cow::Borrowed(_) => word,
+            Cow::Owned(normal) => normal,
+        }
+    }
+}
+
 /// Filter (accept or reject) the specified word.
 ///
 /// For a given `word` from a natural language dictionary (different
@@ -55,19 +150,20 @@ const PRIMES: [u16; 200] =
 /// phrase but counting only alphabetic characters; `input_product` is
 /// the mathematical product of multiplying all prime numbers
 /// associated with all alphanumeric characters (not just uniques)
-/// from the input phrase.
+/// from the input phrase; `alphabet` is that of its natural language.
 pub fn filter_word(
     word: &str, pattern: &str, input_length: usize, input_product: &BigUint,
+    alphabet: Alphabet,
 ) -> Result<BigUint> {
-    let word_chars = essential_chars(word);
+    let word_chars = essential_chars(word, alphabet);
     if word_chars.len() > input_length {
         return Err(AnagramError::WordTooLong);
     }
-    let unique_chars = extract_unique_chars(word);
+    let unique_chars = extract_unique_chars(word, alphabet);
     if !matched_chars(&unique_chars, pattern) {
         return Err(AnagramError::MismatchedChars);
     }
-    let product = primes_product(&primes(&word_chars)?)?;
+    let product = primes_product(&primes(&word_chars, alphabet)?)?;
     if product > *input_product {
         return Err(AnagramError::WordProductTooBig);
     }
@@ -79,10 +175,11 @@ pub fn filter_word(
 
 /// Extract non-duplicate characters in preparation for pattern-matching
 #[allow(clippy::map_entry)]
-pub fn extract_unique_chars(input_string: &str) -> String {
+pub fn extract_unique_chars(input_string: &str, alphabet: Alphabet) -> String {
     let mut pattern = String::with_capacity(input_string.len());
     let mut map: BTreeMap<char, bool> = BTreeMap::new();
-    for ch in input_string.to_lowercase().chars() {
+    let lowercase = alphabet.casing.lowercase(&alphabet.compose(input_string));
+    for ch in alphabet.expand(&lowercase).chars().map(|c| alphabet.letter(c)) {
         if ch.is_alphabetic() && !map.contains_key(&ch) {
             map.insert(ch, true);
             pattern.push(ch);
@@ -94,9 +191,10 @@ pub fn extract_unique_chars(input_string: &str) -> String {
 /// Extract alphabetic characters while allowing duplicate characters
 /// but ignoring white-space, hyphens, apostrophes, etc.
 /// e.g., for determining length used while filtering word list entries
-pub fn essential_chars(input_string: &str) -> String {
+pub fn essential_chars(input_string: &str, alphabet: Alphabet) -> String {
     let mut pattern = String::with_capacity(input_string.len());
-    for ch in input_string.to_lowercase().chars() {
+    let lowercase = alphabet.casing.lowercase(&alphabet.compose(input_string));
+    for ch in alphabet.expand(&lowercase).chars().map(|c| alphabet.letter(c)) {
         if ch.is_alphabetic() {
             pattern.push(ch);
         }
@@ -116,15 +214,15 @@ pub fn matched_chars(word: &str, pattern: &str) -> bool {
 
 /// Hash a string's "essential" characters to a sequence of prime numbers.
 /// See `essential_chars()`.
-pub fn primes(essential: &str) -> Result<Vec<u16>> {
+pub fn primes(essential: &str, alphabet: Alphabet) -> Result<Vec<u16>> {
     let mut result = Vec::with_capacity(essential.len());
-    for ch in essential.chars() {
-        if let Some(index) = hash(ch) {
+    for ch in alphabet.expand(essential).chars().map(|c| alphabet.letter(c)) {
+        if let Some(index) = hash(ch, alphabet.script) {
             result.push(PRIMES[index]);
         } else {
             // Probably char_seq::hasher() is incomplete.
             // Perhaps .to_lowercase() didn't work as expected?
-            println!(
+            eprintln!(
                 "Error: unable to select a prime \
                       for '{}' (U+{:04x}) in \"{}\"",
                 ch, ch as usize, &essential
@@ -150,10 +248,59 @@ pub fn primes_product(primes: &[u16]) -> Result<BigUint> {
     Ok(result)
 }
 
-/// Map a char code-point from ISO-8859-* to index within `PRIMES`
+/// Number of lowercase letters of Cyrillic mapped ahead of Latin
+#[cfg(not(feature = "external-hasher"))]
+const CYRILLIC_LEN: usize = 49;
+
+/// Number of lowercase letters of Greek mapped ahead of Latin
+#[cfg(not(feature = "external-hasher"))]
+const GREEK_LEN: usize = 36;
+
+/// Map a char code-point to index within `PRIMES`, where lowercase
+/// letters of `script` come first followed by those of ISO-8859-1.
+///
+/// Only the letters of `script` and Latin letters are accommodated,
+/// so Greek within Cyrillic text, or vice-versa, returns `None`.  Each
+/// mapping is distinct per script, so products of primes from
+/// different scripts must not be mixed.
+#[cfg(not(feature = "external-hasher"))]
+#[inline]
+pub fn hash(ch: char, script: Script) -> Option<usize> {
+    match script {
+        Script::Latin => latin(ch),
+        Script::Cyrillic => cyrillic(ch).or_else(|| Some(CYRILLIC_LEN + latin(ch)?)),
+        Script::Greek => greek(ch).or_else(|| Some(GREEK_LEN + latin(ch)?)),
+    }
+}
+
+/// Index of lowercase Cyrillic, covering Russian and Ukrainian:
+/// а=U+0430 through џ=U+045F plus Ukrainian ґ=U+0491
+#[cfg(not(feature = "external-hasher"))]
+#[inline]
+fn cyrillic(ch: char) -> Option<usize> {
+    match ch {
+        '\u{0430}'..='\u{045F}' => Some(ch as usize - 0x0430),
+        '\u{0491}' => Some(CYRILLIC_LEN - 1),
+        _ => None,
+    }
+}
+
+/// Index of lowercase Greek, including accented vowels and final
+/// sigma: ά=U+03AC through ώ=U+03CE plus ΐ=U+0390
 #[cfg(not(feature = "external-hasher"))]
 #[inline]
-pub fn hash(ch: char) -> Option<usize> {
+fn greek(ch: char) -> Option<usize> {
+    match ch {
+        '\u{03AC}'..='\u{03CE}' => Some(ch as usize - 0x03AC),
+        '\u{0390}' => Some(GREEK_LEN - 1),
+        _ => None,
+    }
+}
+
+/// Index of char code-point from ISO-8859-1
+#[cfg(not(feature = "external-hasher"))]
+#[inline]
+fn latin(ch: char) -> Option<usize> {
     if ch.is_ascii_lowercase() {
         // Accommodate all of ISO-8859-1 through -16
         // ASCII a=97,U+61, z=122,U+7A; 26 lowercase characters
@@ -165,13 +312,22 @@ pub fn hash(ch: char) -> Option<usize> {
         // integrity for Cyrillic in iso-8859-5
         Some(26 + ch as usize - 0xA1)
     } else {
-        None
+        // Beyond ISO-8859-1 for Turkish ğ, ı, ş and ligatures ĳ, œ
+        LATIN_EXTENDED.iter().position(|&c| c == ch).map(|i| 26 + 95 + i)
     }
 }
 
+/// Lowercase letters beyond ISO-8859-1 mapped after it, such as for
+/// Turkish where dotless ı is a letter distinct from i, and ligatures
+/// ĳ and œ for languages without [crate::languages::EXPANSIONS] of them.
+/// (Ligature æ=U+00E6 is already within ISO-8859-1.)
+#[cfg(not(feature = "external-hasher"))]
+const LATIN_EXTENDED: [char; 5] =
+    ['\u{011F}', '\u{0131}', '\u{015F}', '\u{0133}', '\u{0153}'];
+
 #[cfg(feature = "external-hasher")]
 #[inline]
-pub fn hash(ch: char) -> Option<usize> {
+pub fn hash(ch: char, _script: Script) -> Option<usize> {
     // To replace this external dependency with your own, the
     // Cargo Guide section on Overriding Dependencies might help:
     //https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html#overriding-dependencies
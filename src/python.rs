@@ -0,0 +1,352 @@
+//! Python extension module via PyO3, such as for prototyping within
+//! Jupyter notebooks.
+//!
+//! Build via `maturin develop --features python`, which adds
+//! `pyo3/extension-module` itself.
+//!
+//! ```python
+//! import anagram_phrases as ap
+//! config = ap.Config(lang="en", dict_file_paths=["/usr/share/dict/words"])
+//! lexicon = ap.preload(config)
+//! search = ap.Search(["new", "south", "wales"], config)
+//! transpositions = search.transpositions(lexicon)
+//! anagrams = search.brute_force(lexicon, max_duration=9.0)
+//! for phrase in search.stream(lexicon):
+//!     print(phrase)
+//! ```
+//!
+//! The GIL gets released while loading word lists and searching.
+
+use num_bigint::BigUint;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::AnagramError;
+use crate::languages::{Encoding, Language, Normalization, Region};
+use crate::primes::PMap;
+use crate::search::{self, UniqueAnagram};
+use crate::sources::Sources;
+use crate::words::{self, Cache};
+
+create_exception!(anagram_phrases, AnagramPhrasesError, PyException);
+
+impl From<AnagramError> for PyErr {
+    fn from(error: AnagramError) -> PyErr {
+        match error {
+            AnagramError::IoError(e) => PyOSError::new_err(e.to_string()),
+            e => AnagramPhrasesError::new_err(e.to_string()),
+        }
+    }
+}
+
+/// Python module `anagram_phrases`
+#[pymodule]
+pub fn anagram_phrases(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyConfig>()?;
+    m.add_class::<Lexicon>()?;
+    m.add_class::<PySearch>()?;
+    m.add_class::<Phrases>()?;
+    m.add_function(wrap_pyfunction!(preload, m)?)?;
+    m.add_function(wrap_pyfunction!(load_and_select, m)?)?;
+    m.add("AnagramPhrasesError", m.py().get_type::<AnagramPhrasesError>())?;
+    Ok(())
+}
+
+/// Wrapper of [Config] with the same defaults as the CLI.
+#[pyclass(name = "Config", frozen)]
+#[derive(Clone)]
+pub struct PyConfig(Config);
+
+#[pymethods]
+impl PyConfig {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (lang="EN", dict_file_paths=vec![PathBuf::from("/usr/share/dict/words")],
+                        encoding="UTF_8", max_phrase_words=0, include_short=false,
+                        include_upcase=false, dict_glob=None, dedup_case=false,
+                        dedup_diacritics=false, multiword_cost=None,
+                        no_multiword=false, exclude_files=vec![], allow_files=vec![],
+                        region="Any", rules_file=None, fold_diacritics=false,
+                        normalization="NFC", source_weights=BTreeMap::new(),
+                        source_limits=BTreeMap::new()))]
+    fn new(
+        lang: &str, dict_file_paths: Vec<PathBuf>, encoding: &str,
+        max_phrase_words: usize, include_short: bool, include_upcase: bool,
+        dict_glob: Option<&str>, dedup_case: bool, dedup_diacritics: bool,
+        multiword_cost: Option<usize>, no_multiword: bool, exclude_files: Vec<PathBuf>,
+        allow_files: Vec<PathBuf>, region: &str, rules_file: Option<PathBuf>,
+        fold_diacritics: bool, normalization: &str,
+        source_weights: BTreeMap<String, f64>, source_limits: BTreeMap<String, usize>,
+    ) -> PyResult<PyConfig> {
+        let dict_glob = dict_glob
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        if multiword_cost == Some(0) {
+            return Err(PyValueError::new_err("multiword_cost must be at least 1"));
+        }
+        Ok(PyConfig(Config {
+            lang: Language::from_str(lang)?,
+            region: Region::from(region),
+            rules_file,
+            dict_file_paths,
+            encoding: parse_encoding(encoding)?,
+            max_phrase_words,
+            multiword_cost,
+            no_multiword,
+            include_short,
+            include_upcase,
+            fold_diacritics,
+            normalization: parse_normalization(normalization)?,
+            dict_glob,
+            source_weights: source_weights.into_iter().collect(),
+            source_limits: source_limits.into_iter().collect(),
+            dedup_case,
+            dedup_diacritics,
+            exclude_files,
+            allow_files,
+        }))
+    }
+
+    #[getter]
+    fn lang(&self) -> String {
+        format!("{:?}", self.0.lang)
+    }
+
+    #[getter]
+    fn dict_file_paths(&self) -> Vec<PathBuf> {
+        self.0.dict_file_paths.clone()
+    }
+
+    #[getter]
+    fn max_phrase_words(&self) -> usize {
+        self.0.max_phrase_words
+    }
+
+    #[getter]
+    fn include_short(&self) -> bool {
+        self.0.include_short
+    }
+
+    #[getter]
+    fn include_upcase(&self) -> bool {
+        self.0.include_upcase
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Word list loaded via [preload] or [load_and_select], and shared
+/// without copying across queries and streaming threads, along with
+/// [Sources] of its words for `source_weights` and `source_limits`.
+#[pyclass(frozen)]
+pub struct Lexicon(Arc<PMap>, Arc<Sources>);
+
+#[pymethods]
+impl Lexicon {
+    /// Number of distinct sets of letters
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// All words of the word list
+    fn words(&self) -> Vec<String> {
+        self.0.values().flatten().cloned().collect()
+    }
+}
+
+/// Load entire word list per `config` once for many queries; see
+/// [words::preload_sources].
+#[pyfunction]
+fn preload(py: Python<'_>, config: &PyConfig) -> PyResult<Lexicon> {
+    let (map, sources) = py.allow_threads(|| words::preload_sources(&config.0, false))?;
+    Ok(Lexicon(Arc::new(map), Arc::new(sources)))
+}
+
+/// Load only words that may contribute to resolving `search`; see
+/// [words::load_and_select_sources].
+///
+/// Returns tuple of [Lexicon] and list of single word matches.
+#[pyfunction]
+#[pyo3(signature = (search, exclude=vec![]))]
+fn load_and_select(
+    py: Python<'_>, search: &PySearch, exclude: Vec<String>,
+) -> PyResult<(Lexicon, Vec<String>)> {
+    let (map, singles, sources) = py.allow_threads(|| {
+        let query =
+            search::Search::query(&search.input_phrase, &search.include, &search.config)?;
+        words::load_and_select_sources(
+            &search.config,
+            &query.pattern,
+            &query.essential,
+            &query.primes_product,
+            &exclude,
+        )
+    })?;
+    Ok((Lexicon(Arc::new(map), Arc::new(sources)), singles))
+}
+
+/// Owned equivalent of [search::Search::query] with metadata computed
+/// from the query.
+#[pyclass(name = "Search", frozen)]
+pub struct PySearch {
+    input_phrase: Vec<String>,
+    include: Vec<String>,
+    config: Config,
+    #[pyo3(get)]
+    pattern: String,
+    #[pyo3(get)]
+    essential: String,
+    #[pyo3(get)]
+    primes: Vec<u16>,
+    #[pyo3(get)]
+    primes_product: BigUint,
+}
+
+#[pymethods]
+impl PySearch {
+    /// `max_phrase_words` of 0 within `config` defaults to one more than
+    /// number of words within `input_phrase` and a minimum of 3.
+    #[new]
+    #[pyo3(signature = (input_phrase, config, include=vec![]))]
+    fn new(
+        input_phrase: Vec<String>, config: &PyConfig, include: Vec<String>,
+    ) -> PyResult<Self> {
+        let mut config = config.0.clone();
+        if config.max_phrase_words == 0 {
+            config.max_phrase_words = std::cmp::max(input_phrase.len() + 1, 3);
+        }
+        let query = search::Search::query(&input_phrase, &include, &config)?;
+        let (pattern, essential, primes, primes_product) =
+            (query.pattern, query.essential, query.primes, query.primes_product);
+        Ok(PySearch {
+            input_phrase,
+            include,
+            config,
+            pattern,
+            essential,
+            primes,
+            primes_product,
+        })
+    }
+
+    #[getter]
+    fn input_phrase(&self) -> Vec<String> {
+        self.input_phrase.clone()
+    }
+
+    /// Single words of `lexicon` with exactly the same letters
+    #[pyo3(signature = (lexicon, exclude=vec![]))]
+    fn transpositions(&self, lexicon: &Lexicon, exclude: Vec<String>) -> Vec<String> {
+        let mut singles = words::select(&lexicon.0, &self.primes_product, &exclude).1;
+        words::filter_multiword(&self.config, &mut singles);
+        singles
+    }
+
+    /// All multiple word phrases found within `lexicon`, where each
+    /// phrase is a list of terms, and each term is a list of alternative
+    /// words sharing the same letters, ranked per `source_weights`.
+    ///
+    /// `max_duration` is in seconds, and `max_steps` limits iterations.
+    #[pyo3(signature = (lexicon, exclude=vec![], max_duration=None, max_steps=None))]
+    fn brute_force(
+        &self, py: Python<'_>, lexicon: &Lexicon, exclude: Vec<String>,
+        max_duration: Option<f64>, max_steps: Option<usize>,
+    ) -> PyResult<Vec<Vec<Vec<String>>>> {
+        let max_duration = seconds(max_duration)?;
+        py.allow_threads(|| {
+            let query =
+                search::Search::query(&self.input_phrase, &self.include, &self.config)?;
+            let (dict, _) = words::select(&lexicon.0, &query.primes_product, &exclude);
+            let cache = Cache::with_sources(&dict, &lexicon.1);
+            let builder =
+                query.enrich(&cache, None, max_duration).with_max_steps(max_steps);
+            let mut anagrams = builder.brute_force();
+            lexicon.1.rank(&mut anagrams);
+            Ok(anagrams)
+        })
+    }
+
+    /// Same as [PySearch::brute_force] but returns a generator yielding
+    /// each phrase as it is found while searching in the background,
+    /// which therefore ignores `source_weights`.
+    /// Discarding the generator stops the search.
+    #[pyo3(signature = (lexicon, exclude=vec![], max_duration=None))]
+    fn stream(
+        &self, lexicon: &Lexicon, exclude: Vec<String>, max_duration: Option<f64>,
+    ) -> PyResult<Phrases> {
+        let max_duration = seconds(max_duration)?;
+        let (input_phrase, include, config) =
+            (self.input_phrase.clone(), self.include.clone(), self.config.clone());
+        let (map, sources) = (Arc::clone(&lexicon.0), Arc::clone(&lexicon.1));
+        let (tx, rx) = channel();
+        std::thread::spawn(move || {
+            if let Ok(query) = search::Search::query(&input_phrase, &include, &config) {
+                let (dict, _) = words::select(&map, &query.primes_product, &exclude);
+                let cache = Cache::with_sources(&dict, &sources);
+                query.enrich(&cache, Some(tx), max_duration).brute_force();
+            }
+        });
+        Ok(Phrases(Mutex::new(rx)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Search({:?})", self.input_phrase)
+    }
+}
+
+/// Generator of phrases fed by the streaming channel of
+/// [search::SearchBuilder].
+#[pyclass(frozen)]
+pub struct Phrases(Mutex<Receiver<UniqueAnagram>>);
+
+#[pymethods]
+impl Phrases {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> Option<Vec<Vec<String>>> {
+        py.allow_threads(|| match self.0.lock() {
+            Ok(rx) => rx.recv().ok().flatten(),
+            Err(_) => None,
+        })
+    }
+}
+
+fn parse_encoding(encoding: &str) -> PyResult<Encoding> {
+    match encoding.to_uppercase().replace('-', "_").as_str() {
+        "UTF_8" | "UTF8" => Ok(Encoding::Utf_8),
+        "ISO_8859_1" | "LATIN1" => Ok(Encoding::Iso_8859_1),
+        _ => Err(PyValueError::new_err(format!("Unsupported encoding: {encoding}"))),
+    }
+}
+
+fn parse_normalization(normalization: &str) -> PyResult<Normalization> {
+    match normalization.to_uppercase().as_str() {
+        "NFC" => Ok(Normalization::NFC),
+        "NFD" => Ok(Normalization::NFD),
+        _ => Err(PyValueError::new_err(format!(
+            "Unsupported normalization: {normalization}"
+        ))),
+    }
+}
+
+fn seconds(duration: Option<f64>) -> PyResult<Option<Duration>> {
+    duration
+        .map(|s| {
+            Duration::try_from_secs_f64(s)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
+        .transpose()
+}
@@ -3,13 +3,15 @@ use num_integer::Integer;
 use num_traits::identities::One;
 use serde::Serialize;
 use std::collections::{btree_map::Entry, BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::time::{Duration, Instant};
 
 use crate::config::Config;
 use crate::error::Result;
-use crate::primes;
-use crate::words::Cache;
+use crate::primes::{self, Alphabet, PMap};
+use crate::sources::Sources;
+use crate::words::{self, Cache};
 
 /// Values computed from each query.
 /// See also: bin/anagrams.rs CLI Options.
@@ -61,15 +63,16 @@ where
         input_phrase: &'a [String], must_include: &'a [String], config: &'b Config,
     ) -> Result<Search<'a, 'b>> {
         let input_string = input_phrase.join("");
-        let pattern = primes::extract_unique_chars(&input_string);
-        let essential = primes::essential_chars(&input_string);
-        let primes = primes::primes(&essential)?;
+        let alphabet = config.alphabet();
+        let pattern = primes::extract_unique_chars(&input_string, alphabet);
+        let essential = primes::essential_chars(&input_string, alphabet);
+        let primes = primes::primes(&essential, alphabet)?;
         let mut primes_product = primes::primes_product(&primes)?;
 
         if !must_include.is_empty() {
             let s = must_include.join("");
-            let e = primes::essential_chars(&s);
-            let p = primes::primes(&e)?;
+            let e = primes::essential_chars(&s, alphabet);
+            let p = primes::primes(&e, alphabet)?;
             let denominator = primes::primes_product(&p)?;
             primes_product /= denominator;
         }
@@ -94,7 +97,14 @@ where
         &'c self, cache: &'b Cache, tx: Option<Sender<UniqueAnagram>>,
         max_duration: Option<Duration>,
     ) -> SearchBuilder<'a, 'b> {
-        SearchBuilder { query: self, dict: cache, tx, max_duration }
+        SearchBuilder {
+            query: self,
+            dict: cache,
+            tx,
+            max_duration,
+            max_steps: None,
+            cancel: None,
+        }
     }
 
     /// Add reference to word list and its metadata.
@@ -103,10 +113,96 @@ where
     ///
     /// See also fn [enrich].
     pub fn add_cache(&'c self, cache: &'b Cache) -> SearchBuilder<'a, 'b> {
-        SearchBuilder { query: self, dict: cache, tx: None, max_duration: None }
+        SearchBuilder {
+            query: self,
+            dict: cache,
+            tx: None,
+            max_duration: None,
+            max_steps: None,
+            cancel: None,
+        }
     }
 }
 
+/// Transpositions and anagrams found for one query, such as when
+/// resolving many queries in a batch.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Solution {
+    /// Query terms joined by space
+    pub query: String,
+    /// "Transpositions" are results strictly consisting of single words.
+    pub transpositions: Vec<String>,
+    /// "Anagrams" are results strictly consisting of multiple words.
+    pub anagrams: Vec<Vec<Vec<String>>>,
+}
+
+/// Resolve `input_phrase` against an entire word list previously
+/// loaded via [crate::words::preload], which avoids reloading the
+/// dictionary per query such as for batch processing or services.
+///
+/// Parameters have the same semantics as [Search::query],
+/// [crate::words::load_and_select] and [Search::enrich], with `sources`
+/// such as from [crate::words::preload_sources] limiting and ranking
+/// anagrams per `--limit` and `--weight`.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve(
+    input_phrase: &[String], must_include: &[String], must_exclude: &[String],
+    config: &Config, lexicon: &PMap, sources: &Sources,
+    tx: Option<Sender<UniqueAnagram>>, max_duration: Option<Duration>,
+) -> Result<Solution> {
+    let search = Search::query(input_phrase, must_include, config)?;
+    let (dict, mut transpositions) =
+        words::select(lexicon, &search.primes_product, must_exclude);
+    words::filter_multiword(config, &mut transpositions);
+    // When `max_phrase_words` is exactly one (a transposition, not anagram/phrase),
+    // it would have been found above while selecting from dictionary.
+    let anagrams = if config.max_phrase_words > 1 {
+        let cache = Cache::with_sources(&dict, sources);
+        let mut anagrams = search.enrich(&cache, tx, max_duration).brute_force();
+        sources.rank(&mut anagrams);
+        anagrams
+    } else {
+        if let Some(tx) = tx {
+            let _ = tx.send(None);
+        }
+        vec![]
+    };
+    Ok(Solution { query: input_phrase.join(" "), transpositions, anagrams })
+}
+
+/// Count of alphabetic characters across all words of `phrase` per
+/// `alphabet` of its language, such as "ß" counting as two letters for
+/// German, where only the first of each inner [Vec] of alternatives
+/// gets counted.
+pub fn letters(phrase: &[Vec<String>], alphabet: Alphabet) -> usize {
+    phrase
+        .iter()
+        .filter_map(|alternatives| alternatives.first())
+        .map(|word| primes::essential_chars(word, alphabet).chars().count())
+        .sum()
+}
+
+/// Heuristic for how "interesting" a phrase may be, favoring phrases
+/// with fewer yet longer words: sum of squares of each word's count of
+/// alphabetic characters.
+///
+/// Alternatives within the same inner [Vec] share a product of primes
+/// and therefore their count of letters, so only the first gets counted.
+pub fn score(phrase: &[Vec<String>], alphabet: Alphabet) -> usize {
+    phrase
+        .iter()
+        .filter_map(|alternatives| alternatives.first())
+        .map(|word| primes::essential_chars(word, alphabet).chars().count().pow(2))
+        .sum()
+}
+
+/// Greater of `max` and number of words within longest of `phrases`,
+/// such as for grouping by number of words without omitting those
+/// exceeding `max` due to `must_include` or `max` of a different search
+pub fn max_word_count(max: usize, phrases: &[Vec<Vec<String>>]) -> usize {
+    phrases.iter().map(Vec::len).fold(max, std::cmp::max)
+}
+
 /// Envelope for sending each new unique anagram as it is found.
 /// Follows similar semantics of how [Iterator] uses [Option] but adds
 /// an indication of progress.
@@ -129,6 +225,14 @@ pub struct SearchBuilder<'a, 'b> {
 
     /// Expire after time elapses (time-to-live, TTL)
     max_duration: Option<Duration>,
+
+    /// Expire after this many iterations, which is deterministic and
+    /// available where clocks are not; e.g., WebAssembly within browser
+    max_steps: Option<usize>,
+
+    /// Expire once this gets set by another thread, such as upon its
+    /// client disconnecting
+    cancel: Option<&'b AtomicBool>,
 }
 
 impl<'a, 'b, 'c> SearchBuilder<'a, 'b>
@@ -145,7 +249,30 @@ where
         query: &'b Search<'a, 'b>, cache: &'b Cache, tx: Option<Sender<UniqueAnagram>>,
         max_duration: Option<Duration>,
     ) -> SearchBuilder<'a, 'b> {
-        SearchBuilder { query, dict: cache, tx, max_duration }
+        SearchBuilder {
+            query,
+            dict: cache,
+            tx,
+            max_duration,
+            max_steps: None,
+            cancel: None,
+        }
+    }
+
+    /// Limit number of iterations of [brute_force] as alternative to
+    /// or in addition to `max_duration`.
+    pub fn with_max_steps(mut self, max_steps: Option<usize>) -> SearchBuilder<'a, 'b> {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Stop [brute_force] once `cancel` gets set, as alternative to or
+    /// in addition to `max_duration`.
+    pub fn with_cancel(
+        mut self, cancel: Option<&'b AtomicBool>,
+    ) -> SearchBuilder<'a, 'b> {
+        self.cancel = cancel;
+        self
     }
 
     /// Exercise combinations and permutations of dictionary words to
@@ -160,9 +287,30 @@ where
     // Limiting elapsed time is pragmatic but a hack nonetheless.
     // After fixing that defect, keep the feature for HTTP service workers.
     pub fn brute_force(&'c self) -> Vec<Vec<Vec<String>>> {
+        let results = self.brute_force_with(|phrase| match &self.tx {
+            Some(tx) => tx.send(Some(phrase)).is_ok(),
+            None => true,
+        });
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(None);
+        }
+        results
+    }
+
+    /// Same as [brute_force] but calls `visit` with each new unique
+    /// phrase as it is found, rather than sending via channel, such as
+    /// for yielding results incrementally where threads are unavailable.
+    ///
+    /// Stops early when `visit` returns `false`.
+    pub fn brute_force_with<F>(&'c self, mut visit: F) -> Vec<Vec<Vec<String>>>
+    where
+        F: FnMut(Vec<Vec<String>>) -> bool,
+    {
         let task = Task::new(self);
         let mut results = Candidate::new();
-        let time = Instant::now();
+        // Clock only when necessary, as some platforms lack one
+        let time = self.max_duration.map(|x| (Instant::now(), x));
+        let mut steps = 0;
         let limit = self.dict.descending_keys.len();
         let mut deque = VecDeque::<Task<'a, 'b>>::with_capacity(limit * 2);
         // TODO allocates new accumulaters, each with one word spanning entire dict
@@ -174,10 +322,8 @@ where
                 State::Complete((task, mut anagram)) => {
                     deque.push_back(task);
                     if let Some(p) = results.push_if_unique(&mut anagram.phrase) {
-                        if let Some(tx) = &self.tx {
-                            if tx.send(Some(p)).is_err() {
-                                break;
-                            }
+                        if !visit(p) {
+                            return results.phrases();
                         }
                     }
                 }
@@ -196,10 +342,8 @@ where
                 State::Complete((task, mut anagram)) => {
                     deque.push_front(task);
                     if let Some(p) = results.push_if_unique(&mut anagram.phrase) {
-                        if let Some(tx) = &self.tx {
-                            if tx.send(Some(p)).is_err() {
-                                break;
-                            }
+                        if !visit(p) {
+                            break;
                         }
                     }
                 }
@@ -208,14 +352,18 @@ where
                     deque.push_front(new_task);
                 }
             }
-            if let Some(x) = self.max_duration {
+            if let Some((time, x)) = time {
                 if time.elapsed() > x {
                     break;
                 }
             }
-        }
-        if let Some(tx) = &self.tx {
-            let _ = tx.send(None);
+            steps += 1;
+            if self.max_steps.is_some_and(|max| steps >= max) {
+                break;
+            }
+            if self.cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                break;
+            }
         }
         results.phrases()
     }
@@ -354,6 +502,9 @@ struct Task<'a, 'b> {
     accumulator: Vec<&'b [String]>,
     /// Product of all primes within `accumulator` (or default value: 1)
     acc_product: BigUint,
+    /// Count of words per source within `accumulator` when [Cache]
+    /// limits any, or otherwise empty
+    tally: Vec<usize>,
 }
 
 impl<'a, 'b> Task<'a, 'b> {
@@ -372,6 +523,7 @@ impl<'a, 'b> Task<'a, 'b> {
             max_words: builder.query.config.max_phrase_words,
             accumulator,
             acc_product: BigUint::one(),
+            tally: vec![0; builder.dict.limits.len()],
         }
     }
 
@@ -388,45 +540,67 @@ impl<'a, 'b> Task<'a, 'b> {
         if test_product > &self.target {
             return State::Unchanged(Task { index: i + 1, ..self });
         }
-        // By virtue of `descending_keys` this IF LET will always succeed
-        if let Some(words) = self.search.dict.lexicon.get(test_product) {
-            let mut accumulator = self.accumulator.clone();
-            accumulator.push(words);
-            if test_product == &self.target {
-                let task = Task { index: i + 1, ..self };
-                let anagram = Anagram { search: self.search, phrase: accumulator };
-                return State::Complete((task, anagram));
-            }
-            let acc_product = test_product * &self.acc_product;
-            if acc_product == self.search.query.primes_product {
-                let task = Task { index: i + 1, ..self };
-                let anagram = Anagram { search: self.search, phrase: accumulator };
-                return State::Complete((task, anagram));
-            }
-            // Prepare forking the accumulator which should only be allowed
-            // when both the phrase's length and its primes' product allow,
-            // but do the computationally cheaper test first (re: BigInt).
-            if self.max_words == 1 || acc_product > self.search.query.primes_product {
-                return State::Reject;
+        let config = self.search.query.config;
+        let cost = match self.search.dict.word_counts[i] {
+            1 => 1,
+            _ if config.no_multiword => usize::MAX,
+            n => config.multiword_cost.unwrap_or(n),
+        };
+        // A multi-word lexeme may exceed remaining words of this phrase
+        if cost > self.max_words {
+            return State::Unchanged(Task { index: i + 1, ..self });
+        }
+        let source = self.search.dict.sources[i];
+        if let Some(Some(limit)) = self.search.dict.limits.get(source) {
+            if self.tally[source] >= *limit {
+                return State::Unchanged(Task { index: i + 1, ..self });
             }
-            // Extend current phrase via branching and without repeating earlier words.
-            // (Smaller values of `i` from parent loop were already tried.)
-            let (quotient, remainder) = self.target.div_rem(test_product);
-            if remainder == BigUint::ZERO {
-                let task = Task { index: i + 1, ..self };
-                // Continue with same `i` in case of repeated words.
-                // Decrement `max_words` due to having pushed `word` above.
-                let branch = Task {
-                    target: quotient,
-                    max_words: self.max_words - 1,
-                    accumulator,
-                    acc_product,
-                    ..self
-                };
-                return State::Branch((task, branch));
+        }
+        let words = &*self.search.dict.alternatives[i];
+        let mut accumulator = self.accumulator.clone();
+        accumulator.push(words);
+        if test_product == &self.target {
+            let task = Task { index: i + 1, ..self };
+            let anagram = Anagram { search: self.search, phrase: accumulator };
+            return State::Complete((task, anagram));
+        }
+        let acc_product = test_product * &self.acc_product;
+        if acc_product == self.search.query.primes_product {
+            let task = Task { index: i + 1, ..self };
+            let anagram = Anagram { search: self.search, phrase: accumulator };
+            return State::Complete((task, anagram));
+        }
+        // Prepare forking the accumulator which should only be allowed
+        // when both the phrase's length and its primes' product allow,
+        // but do the computationally cheaper test first (re: BigInt).
+        if self.max_words == 1 || acc_product > self.search.query.primes_product {
+            return State::Reject;
+        }
+        if cost >= self.max_words {
+            return State::Unchanged(Task { index: i + 1, ..self });
+        }
+        // Extend current phrase via branching and without repeating earlier words.
+        // (Smaller values of `i` from parent loop were already tried.)
+        let (quotient, remainder) = self.target.div_rem(test_product);
+        if remainder == BigUint::ZERO {
+            let mut tally = self.tally.clone();
+            if let Some(count) = tally.get_mut(source) {
+                *count += 1;
             }
+            let task = Task { index: i + 1, ..self };
+            // Continue with same `i` in case of repeated words.
+            // Deduct `max_words` due to having pushed `word` above.
+            let branch = Task {
+                target: quotient,
+                max_words: self.max_words - cost,
+                accumulator,
+                acc_product,
+                tally,
+                ..self
+            };
+            return State::Branch((task, branch));
         }
-        return State::Unchanged(Task { index: i + 1, ..self });
+        State::Unchanged(Task { index: i + 1, ..self })
     }
 }
 
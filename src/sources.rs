@@ -0,0 +1,161 @@
+//! Tagged and weighted word lists loaded together for a single search,
+//! such as `core`, `slang` and `proper-nouns`, retaining which word
+//! came from which word list.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::words::{Dedup, STDIN_PATH};
+
+/// Tag of word list read from stdin, unless tagged otherwise
+pub const STDIN_TAG: &str = "stdin";
+
+/// Separator of tag from path such as `slang=~/slang.txt`
+pub const TAG_SEPARATOR: char = '=';
+
+/// One or more word lists sharing the same tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+    /// Name such as `core` or `slang`
+    pub tag: String,
+    /// Phrases containing words from sources of greater weight get
+    /// listed first; defaults to 1.0.
+    pub weight: f64,
+    /// At most this many words from this source within each phrase
+    pub limit: Option<usize>,
+}
+
+/// Provenance of every word loaded, by [Source].
+///
+/// Words are looked up with the same folding as applied while
+/// de-duplicating, so the tag of a merged variant is that of the
+/// spelling loaded first.
+#[derive(Clone, Debug, Default)]
+pub struct Sources {
+    /// Each distinct tag in order loaded
+    pub tags: Vec<Source>,
+    dedup: Dedup,
+    words: HashMap<String, usize>,
+}
+
+impl Sources {
+    /// Constructor applying `--weight` and `--limit` of `config` to
+    /// each of `tags`, where `words` maps each word per `dedup` to its
+    /// index within `tags`.
+    pub(crate) fn new(
+        config: &Config, tags: Vec<String>, dedup: Dedup, words: HashMap<String, usize>,
+    ) -> Sources {
+        let tags = tags
+            .into_iter()
+            .map(|tag| {
+                let weight = config
+                    .source_weights
+                    .iter()
+                    .rfind(|(t, _)| *t == tag)
+                    .map_or(1.0, |(_, w)| *w);
+                let limit = config
+                    .source_limits
+                    .iter()
+                    .rfind(|(t, _)| *t == tag)
+                    .map(|(_, n)| *n);
+                Source { tag, weight, limit }
+            })
+            .collect();
+        Sources { tags, dedup, words }
+    }
+
+    /// Index within `tags` of the source of `word`
+    pub fn index(&self, word: &str) -> Option<usize> {
+        self.words.get(&self.dedup.key(word)).copied()
+    }
+
+    /// Source from which `word` was loaded
+    pub fn source(&self, word: &str) -> Option<&Source> {
+        self.index(word).map(|i| &self.tags[i])
+    }
+
+    /// Tag of the source from which `word` was loaded
+    pub fn tag(&self, word: &str) -> Option<&str> {
+        self.source(word).map(|s| s.tag.as_str())
+    }
+
+    /// Weight of `phrase` as that of its least weighted term, since a
+    /// phrase is only as common as its oddest word.  Only the first of
+    /// alternatives within each term gets considered.
+    pub fn weight(&self, phrase: &[Vec<String>]) -> f64 {
+        phrase
+            .iter()
+            .filter_map(|alternatives| alternatives.first())
+            .map(|word| self.source(word).map_or(1.0, |s| s.weight))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Sort `phrases` by weight, greatest first, retaining existing
+    /// order among those of equal weight.
+    pub fn rank(&self, phrases: &mut [Vec<Vec<String>>]) {
+        phrases.sort_by(|a, b| self.weight(b).total_cmp(&self.weight(a)));
+    }
+
+    /// Tag of each word within `singles` and `phrases`, including
+    /// alternatives, when loaded from more than one source.
+    pub fn provenance(
+        &self, singles: &[String], phrases: &[Vec<Vec<String>>],
+    ) -> BTreeMap<String, String> {
+        let mut tags = BTreeMap::new();
+        if self.tags.len() < 2 {
+            return tags;
+        }
+        let words = phrases.iter().flatten().flatten();
+        for word in singles.iter().chain(words) {
+            if let Some(tag) = self.tag(word) {
+                tags.insert(word.to_string(), tag.to_string());
+            }
+        }
+        tags
+    }
+
+    /// Limit of each source in order of `tags`, or empty when none
+    pub fn limits(&self) -> Vec<Option<usize>> {
+        match self.tags.iter().any(|s| s.limit.is_some()) {
+            true => self.tags.iter().map(|s| s.limit).collect(),
+            false => vec![],
+        }
+    }
+}
+
+/// Split `TAG=PATH` into its tag and path, unless `path` exists as
+/// specified.  Without a tag, the tag is the file name up to its first
+/// `.` such as `words` for `/usr/share/dict/words` or `slang` for
+/// `slang.txt.gz`.
+pub fn split_tag(path: &Path) -> (String, PathBuf) {
+    if !path.exists() {
+        if let Some((tag, rest)) = path.to_str().and_then(|s| s.split_once(TAG_SEPARATOR))
+        {
+            if !tag.is_empty() && !tag.contains(std::path::is_separator) {
+                return (tag.to_string(), PathBuf::from(rest));
+            }
+        }
+    }
+    if path == Path::new(STDIN_PATH) {
+        return (STDIN_TAG.to_string(), path.to_path_buf());
+    }
+    let name = path.file_name().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let tag = name.split('.').next().unwrap_or_default();
+    (tag.to_string(), path.to_path_buf())
+}
+
+/// Parse `TAG=VALUE` such as for `--weight` and `--limit`
+pub fn parse_tagged<T>(arg: &str) -> std::result::Result<(String, T), String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match arg.split_once(TAG_SEPARATOR) {
+        Some((tag, value)) if !tag.is_empty() => {
+            let value = value.parse().map_err(|e| format!("{value:?}: {e}"))?;
+            Ok((tag.to_string(), value))
+        }
+        _ => Err(format!("expected TAG{TAG_SEPARATOR}VALUE but got {arg:?}")),
+    }
+}
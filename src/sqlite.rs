@@ -0,0 +1,146 @@
+//! Write results into SQLite database with a normalized, queryable schema.
+//!
+//! Unlike CSV with its multiple sections, each query appends rows so
+//! that results of many runs may be filtered by word, length or score
+//! using SQL; e.g.,
+//!
+//! ```sql
+//! SELECT phrase, score FROM results WHERE word_count = 2 ORDER BY score DESC;
+//! ```
+
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::primes::{self, Alphabet};
+use crate::search;
+
+/// Tables for queries, phrases, words and same-product alternatives.
+///
+/// Each phrase consists of `terms` (one per position within phrase),
+/// and each term has one or more `alternatives` being words with an
+/// identical product of primes.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS queries (
+    id INTEGER PRIMARY KEY,
+    input_phrase TEXT NOT NULL,
+    lang TEXT NOT NULL,
+    max_words INTEGER NOT NULL,
+    created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE IF NOT EXISTS words (
+    id INTEGER PRIMARY KEY,
+    word TEXT NOT NULL UNIQUE,
+    letters INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS phrases (
+    id INTEGER PRIMARY KEY,
+    query_id INTEGER NOT NULL REFERENCES queries(id),
+    kind TEXT NOT NULL CHECK (kind IN ('transposition', 'anagram')),
+    word_count INTEGER NOT NULL,
+    letters INTEGER NOT NULL,
+    score INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS terms (
+    id INTEGER PRIMARY KEY,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id),
+    position INTEGER NOT NULL,
+    UNIQUE (phrase_id, position)
+);
+CREATE TABLE IF NOT EXISTS alternatives (
+    term_id INTEGER NOT NULL REFERENCES terms(id),
+    word_id INTEGER NOT NULL REFERENCES words(id),
+    PRIMARY KEY (term_id, word_id)
+);
+CREATE INDEX IF NOT EXISTS phrases_query ON phrases(query_id);
+CREATE INDEX IF NOT EXISTS alternatives_word ON alternatives(word_id);
+CREATE VIEW IF NOT EXISTS results AS
+    SELECT queries.input_phrase AS query, phrases.id AS phrase_id, kind,
+           word_count, phrases.letters, score,
+           group_concat(term, ' ') AS phrase
+    FROM (SELECT terms.phrase_id, terms.position,
+                 group_concat(words.word, '|') AS term
+          FROM terms
+          JOIN alternatives ON alternatives.term_id = terms.id
+          JOIN words ON words.id = alternatives.word_id
+          GROUP BY terms.id
+          ORDER BY terms.phrase_id, terms.position)
+    JOIN phrases ON phrases.id = phrase_id
+    JOIN queries ON queries.id = phrases.query_id
+    GROUP BY phrases.id;
+";
+
+/// Append transpositions and anagrams of `input_phrase` into SQLite
+/// database file, which gets created when necessary.
+///
+/// See also: fn [write_to].
+pub fn write(
+    filepath: &Path, input_phrase: &str, lang: &str, alphabet: Alphabet, max: usize,
+    singles: &[String], phrases: &[Vec<Vec<String>>],
+) -> Result<()> {
+    let mut conn = Connection::open(filepath)?;
+    write_to(&mut conn, input_phrase, lang, alphabet, max, singles, phrases)
+}
+
+/// Append transpositions and anagrams of `input_phrase` using an
+/// existing connection such as to an in-memory database, where letters
+/// get counted per `alphabet` of `lang`.
+pub fn write_to(
+    conn: &mut Connection, input_phrase: &str, lang: &str, alphabet: Alphabet,
+    max: usize, singles: &[String], phrases: &[Vec<Vec<String>>],
+) -> Result<()> {
+    conn.execute_batch(SCHEMA)?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO queries (input_phrase, lang, max_words) VALUES (?1, ?2, ?3)",
+        params![input_phrase, lang, max],
+    )?;
+    let query_id = tx.last_insert_rowid();
+    for word in singles {
+        insert_phrase(&tx, query_id, "transposition", &[vec![word.clone()]], alphabet)?;
+    }
+    for terms in phrases {
+        insert_phrase(&tx, query_id, "anagram", terms, alphabet)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Insert one phrase with its terms and their alternatives
+fn insert_phrase(
+    tx: &Transaction, query_id: i64, kind: &str, terms: &[Vec<String>],
+    alphabet: Alphabet,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO phrases (query_id, kind, word_count, letters, score)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            query_id,
+            kind,
+            terms.len(),
+            search::letters(terms, alphabet),
+            search::score(terms, alphabet)
+        ],
+    )?;
+    let phrase_id = tx.last_insert_rowid();
+    let mut insert_term =
+        tx.prepare_cached("INSERT INTO terms (phrase_id, position) VALUES (?1, ?2)")?;
+    let mut insert_word = tx.prepare_cached(
+        "INSERT INTO words (word, letters) VALUES (?1, ?2)
+         ON CONFLICT (word) DO UPDATE SET word = word RETURNING id",
+    )?;
+    let mut insert_alternative = tx.prepare_cached(
+        "INSERT OR IGNORE INTO alternatives (term_id, word_id) VALUES (?1, ?2)",
+    )?;
+    for (position, alternatives) in terms.iter().enumerate() {
+        insert_term.execute(params![phrase_id, position])?;
+        let term_id = tx.last_insert_rowid();
+        for word in alternatives {
+            let letters = primes::essential_chars(word, alphabet).chars().count();
+            let word_id: i64 =
+                insert_word.query_row(params![word, letters], |row| row.get(0))?;
+            insert_alternative.execute(params![term_id, word_id])?;
+        }
+    }
+    Ok(())
+}
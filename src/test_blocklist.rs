@@ -0,0 +1,92 @@
+use crate::blocklist::Blocklist;
+use crate::config::Config;
+use crate::languages::{Casing, Language, Rules};
+use crate::search::Search;
+use crate::test_helpers::word_list;
+use crate::words::{self, Dedup};
+
+#[test]
+fn folded_matching() {
+    let blocklist = Blocklist::from_words(&["Slut", "élan"], &["LUST"], Casing::Unicode);
+    assert!(blocklist.is_blocked("slut"));
+    assert!(blocklist.is_blocked("SLUT"));
+    assert!(blocklist.is_blocked("Elan"));
+    assert!(!blocklist.is_blocked("lust"));
+    assert!(blocklist.is_allowed("Lust"));
+    assert!(!blocklist.is_allowed("slut"));
+
+    let both = Blocklist::from_words(&["slut"], &["slut"], Casing::Unicode);
+    assert!(!both.is_blocked("slut"));
+    assert!(!Blocklist::default().is_blocked("slut"));
+
+    // Turkish: I lowercases to dotless ı, which remains distinct from i
+    let turkish = Blocklist::from_words(&["KIZ"], &[], Casing::Turkic);
+    assert!(turkish.is_blocked("kız"));
+    assert!(!turkish.is_blocked("kiz"));
+    let turkish = Blocklist::from_words(&["İŞ"], &[], Casing::Turkic);
+    assert!(turkish.is_blocked("iş"));
+    assert!(turkish.is_blocked("is"));
+    let unicode = Blocklist::from_words(&["KIZ"], &[], Casing::Unicode);
+    assert!(unicode.is_blocked("kız"));
+    assert!(unicode.is_blocked("kiz"));
+}
+
+#[test]
+fn exclude_and_allow_files() {
+    let dict = word_list("blocklist-dict", &["sale", "seal", "Elsa", "ales", "leas"]);
+    let exclude =
+        word_list("blocklist-exclude", &["# house style", "", "SEAL", "ales", "leas"]);
+    let allow = word_list("blocklist-allow", &["élsa", "leas"]);
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![dict.clone()],
+        exclude_files: vec![exclude.clone()],
+        allow_files: vec![allow.clone()],
+        ..Config::default()
+    };
+    let blocklist = config.blocklist().unwrap();
+    assert!(blocklist.is_blocked("seal"));
+    assert!(!blocklist.is_blocked("leas"));
+
+    let input_phrase = ["seal".to_string()];
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (_, singles) = words::load_and_select(
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(vec!["sale", "Elsa", "leas"], singles);
+
+    let bytes = std::fs::read(&dict).unwrap();
+    let (_, singles) = words::load_and_select_from(
+        bytes.as_slice(),
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(vec!["sale", "Elsa", "leas"], singles);
+
+    let lexicon = words::preload(
+        &[dict.as_path()],
+        &Rules::builtin(&config.lang),
+        &config.encoding,
+        false,
+        false,
+        Dedup::default(),
+        &blocklist,
+        false,
+    )
+    .unwrap();
+    let mut all: Vec<&String> = lexicon.values().flatten().collect();
+    all.sort();
+    assert_eq!(vec!["Elsa", "leas", "sale"], all);
+    for path in [dict, exclude, allow] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
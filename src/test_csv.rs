@@ -0,0 +1,101 @@
+use crate::csv::{self, Layout};
+use crate::languages::Language;
+use crate::primes::Alphabet;
+use crate::test_helpers::{new_south_wales, solutions};
+
+#[test]
+fn sheet_layout() {
+    let singles = vec!["silent".to_string()];
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_to(&mut buffer, 3, &singles, &phrases).unwrap();
+    // Blank separator rows get written as an empty field:
+    let expected = "\
+Transpositions
+silent
+\"\"
+Anagrams
+\"\"
+2 words
+\"\"
+newel,washouts
+\"\"
+3 words
+\"\"
+ale|lea,snouts,whew
+";
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+}
+
+#[test]
+fn flat_layout() {
+    let singles = vec!["silent".to_string()];
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    let alphabet = Alphabet::default();
+    Layout::Flat.write_to(&mut buffer, 3, alphabet, &singles, &phrases).unwrap();
+    let expected = "\
+kind,word_count,letters,phrase,alternatives,score
+transposition,1,6,silent,silent,36
+anagram,2,13,newel washouts,newel washouts,89
+anagram,3,13,ale snouts whew,ale|lea snouts whew,61
+";
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+
+    // Header gets written even without results:
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_flat_to(&mut buffer, 3, alphabet, &[], &[]).unwrap();
+    assert_eq!(b"kind,word_count,letters,phrase,alternatives,score\n", buffer.as_slice());
+
+    // Letters get counted per language, where German ß counts as ss:
+    let german = Alphabet { expansions: Language::DE.expansions(), ..alphabet };
+    let singles = vec!["Straße".to_string()];
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_flat_to(&mut buffer, 1, german, &singles, &[]).unwrap();
+    let rows = String::from_utf8(buffer).unwrap();
+    assert_eq!(Some("transposition,1,7,Straße,Straße,49"), rows.lines().nth(1));
+}
+
+#[test]
+fn batch() {
+    let solutions = solutions();
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_batch_to(&mut buffer, 3, Alphabet::default(), &solutions).unwrap();
+    let expected = "\
+query,kind,word_count,letters,phrase,alternatives,score
+listen,transposition,1,6,silent,silent,36
+new south wales,anagram,2,13,newel washouts,newel washouts,89
+new south wales,anagram,3,13,ale snouts whew,ale|lea snouts whew,61
+";
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+}
+
+#[test]
+fn longer_than_max() {
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_flat_to(&mut buffer, 2, Alphabet::default(), &[], &phrases).unwrap();
+    let rows = String::from_utf8(buffer).unwrap();
+    assert_eq!(3, rows.lines().count());
+    assert_eq!(
+        Some("anagram,3,13,ale snouts whew,ale|lea snouts whew,61"),
+        rows.lines().nth(2)
+    );
+
+    let mut buffer: Vec<u8> = vec![];
+    csv::write_to(&mut buffer, 2, &[], &phrases).unwrap();
+    let expected = "\
+Transpositions
+\"\"
+Anagrams
+\"\"
+2 words
+\"\"
+newel,washouts
+\"\"
+3 words
+\"\"
+ale|lea,snouts,whew
+";
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+}
@@ -0,0 +1,55 @@
+use crate::diff::{self, Changes};
+use crate::test_helpers::phrase;
+
+#[test]
+fn identical() {
+    let singles = vec!["listen".to_string(), "silent".to_string()];
+    let phrases = vec![phrase(&[&["ale", "lea"], &["snouts"], &["whew"]])];
+    // Order of words and of alternatives is insignificant:
+    let reordered = vec![phrase(&[&["whew"], &["lea", "ale"], &["snouts"]])];
+    let changes = diff::compare((&singles, &phrases), (&singles, &reordered));
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn added_and_removed() {
+    let old_singles = vec!["listen".to_string(), "silent".to_string()];
+    let new_singles = vec!["enlist".to_string(), "listen".to_string()];
+    let old_phrases = vec![
+        phrase(&[&["newel"], &["washouts"]]),
+        phrase(&[&["aeons"], &["lust", "slut"], &["whew"]]),
+    ];
+    let new_phrases = vec![
+        phrase(&[&["newel"], &["washouts"]]),
+        phrase(&[&["aeons"], &["lust"], &["whew"]]),
+        phrase(&[&["ah", "ha"], &["tuneless"], &["wow"]]),
+    ];
+    let changes =
+        diff::compare((&old_singles, &old_phrases), (&new_singles, &new_phrases));
+    let expected = Changes {
+        transpositions_added: vec!["enlist".to_string()],
+        transpositions_removed: vec!["silent".to_string()],
+        anagrams_added: vec![
+            phrase(&[&["aeons"], &["lust"], &["whew"]]),
+            phrase(&[&["ah", "ha"], &["tuneless"], &["wow"]]),
+        ],
+        anagrams_removed: vec![phrase(&[&["aeons"], &["lust", "slut"], &["whew"]])],
+    };
+    assert_eq!(expected, changes);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn json_round_trip() {
+    use crate::json;
+    use crate::test_helpers::new_south_wales;
+
+    let singles = vec!["listen".to_string(), "silent".to_string()];
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    json::write_to(&mut buffer, 3, &singles, &phrases).unwrap();
+    let results = json::read_from(buffer.as_slice()).unwrap();
+    assert_eq!(singles, results.transpositions);
+    // Exported anagrams are sorted by number of words:
+    assert_eq!(vec![phrases[1].clone(), phrases[0].clone()], results.anagrams);
+}
@@ -0,0 +1,84 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use crate::ffi::*;
+
+/// Read string returned via C ABI
+unsafe fn to_string(s: *const std::ffi::c_char) -> Option<String> {
+    (!s.is_null()).then(|| CStr::from_ptr(s).to_string_lossy().to_string())
+}
+
+#[test]
+fn lexicon_query_results() {
+    let path = std::env::temp_dir()
+        .join(format!("anagram-phrases-{}-ffi.txt", std::process::id()));
+    std::fs::write(&path, "enlist\nlisten\nsilent\nnewel\nwashouts\n").unwrap();
+    let path = CString::new(path.to_string_lossy().as_bytes()).unwrap();
+    let paths = [path.as_ptr()];
+    unsafe {
+        let mut lexicon = ptr::null_mut();
+        let status =
+            anagram_lexicon_load(c"en".as_ptr(), paths.as_ptr(), 1, &mut lexicon);
+        std::fs::remove_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(AnagramStatus::Ok, status);
+
+        let mut results = ptr::null_mut();
+        let status = anagram_query(lexicon, c"tinsel".as_ptr(), 1, 0, &mut results);
+        assert_eq!(AnagramStatus::Ok, status);
+        assert_eq!(3, anagram_results_transpositions_len(results));
+        assert_eq!(
+            Some("enlist".to_string()),
+            to_string(anagram_results_transposition(results, 0))
+        );
+        assert_eq!(None, to_string(anagram_results_transposition(results, 3)));
+        assert_eq!(0, anagram_results_anagrams_len(results));
+        anagram_results_free(results);
+
+        let status =
+            anagram_query(lexicon, c"new south wales".as_ptr(), 0, 1000, &mut results);
+        assert_eq!(AnagramStatus::Ok, status);
+        assert_eq!(0, anagram_results_transpositions_len(results));
+        assert_eq!(1, anagram_results_anagrams_len(results));
+        assert_eq!(2, anagram_results_terms_len(results, 0));
+        assert_eq!(1, anagram_results_alternatives_len(results, 0, 1));
+        assert_eq!(
+            Some("washouts".to_string()),
+            to_string(anagram_results_word(results, 0, 1, 0))
+        );
+        assert_eq!(None, to_string(anagram_results_word(results, 0, 2, 0)));
+        anagram_results_free(results);
+
+        let status = anagram_query(lexicon, ptr::null(), 0, 0, &mut results);
+        assert_eq!(AnagramStatus::NullArgument, status);
+        anagram_lexicon_free(lexicon);
+    }
+}
+
+#[test]
+fn status_codes() {
+    let mut lexicon = ptr::null_mut();
+    let paths = [c"/nonexistent/words".as_ptr()];
+    unsafe {
+        let status =
+            anagram_lexicon_load(c"xx".as_ptr(), paths.as_ptr(), 1, &mut lexicon);
+        assert_eq!(AnagramStatus::LangNotImplemented, status);
+        let status =
+            anagram_lexicon_load(c"en".as_ptr(), paths.as_ptr(), 1, &mut lexicon);
+        assert_eq!(AnagramStatus::IoError, status);
+        assert!(lexicon.is_null());
+        assert_eq!(
+            Some("IO error".to_string()),
+            to_string(anagram_status_message(status as i32))
+        );
+    }
+    assert_eq!(Some(AnagramStatus::RulesFile), AnagramStatus::from_code(22));
+    assert_eq!(None, AnagramStatus::from_code(4));
+    unsafe {
+        for code in [-1, 4, 23, i32::MAX] {
+            assert_eq!(
+                Some("Unknown status".to_string()),
+                to_string(anagram_status_message(code))
+            );
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by multiple test modules
+
+use std::path::PathBuf;
+
+#[cfg(feature = "cli")]
+use crate::search::Solution;
+
+/// Write `lines` to a uniquely named file within temp directory
+pub fn word_list(name: &str, lines: &[&str]) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("anagram-phrases-{}-{name}.txt", std::process::id()));
+    std::fs::write(&path, lines.join("\n")).unwrap();
+    path
+}
+
+/// Phrase of terms, each with one or more alternative words
+pub fn phrase(terms: &[&[&str]]) -> Vec<Vec<String>> {
+    terms.iter().map(|t| t.iter().map(ToString::to_string).collect()).collect()
+}
+
+/// Anagrams of "new south wales" in order found, where the longer phrase
+/// precedes the shorter such that sorting by number of words is evident
+#[cfg(any(feature = "cli", feature = "sqlite"))]
+pub fn new_south_wales() -> Vec<Vec<Vec<String>>> {
+    vec![
+        phrase(&[&["ale", "lea"], &["snouts"], &["whew"]]),
+        phrase(&[&["newel"], &["washouts"]]),
+    ]
+}
+
+/// Results of a batch: "listen" with its transposition "silent", and
+/// "new south wales" with anagrams per [new_south_wales]
+#[cfg(feature = "cli")]
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution {
+            query: "listen".to_string(),
+            transpositions: vec!["silent".to_string()],
+            anagrams: vec![],
+        },
+        Solution {
+            query: "new south wales".to_string(),
+            transpositions: vec![],
+            anagrams: new_south_wales(),
+        },
+    ]
+}
@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use crate::blocklist::Blocklist;
+use crate::config::Config;
+use crate::hunspell::{self, Affixes};
+use crate::languages::{Encoding, Language, Rules};
+use crate::search::{self, Search};
+use crate::sources::Sources;
+use crate::words::{self, Dedup};
+
+const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+
+# Plurals
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX D N 1
+SFX D   0     ed/S       .
+";
+
+fn expand(aff: &str, dic: &str) -> Vec<String> {
+    hunspell::expand_from(dic.as_bytes(), aff.as_bytes(), &Encoding::Utf_8).unwrap()
+}
+
+#[test]
+fn suffixes_and_prefixes() {
+    let words = expand(AFF, "4\nsky/S\nwashout/SU\nbox/S\nday/S\tpo:noun\n");
+    assert_eq!(
+        vec![
+            "box",
+            "boxes",
+            "day",
+            "days",
+            "skies",
+            "sky",
+            "unwashout",
+            "unwashouts",
+            "washout",
+            "washouts"
+        ],
+        words
+    );
+}
+
+#[test]
+fn continuation_and_need_affix() {
+    let aff = format!("{AFF}NEEDAFFIX X\n");
+    let words = expand(&aff, "1\nwant/DX\n");
+    assert_eq!(vec!["wanted", "wanteds"], words);
+}
+
+#[test]
+fn forbidden_word() {
+    let aff = format!("{AFF}FORBIDDENWORD !\nSFX F Y 1\nSFX F 0 s/! .\n");
+    let words = expand(&aff, "3\nsky/S\nwashout/SU!\nday/F\n");
+    assert_eq!(vec!["day", "skies", "sky"], words);
+}
+
+#[test]
+fn long_and_aliased_flags() {
+    let aff = "FLAG long
+AF 2
+AF S1U1
+AF U1
+SFX S1 Y 1
+SFX S1 0 s .
+PFX U1 Y 1
+PFX U1 0 un .
+";
+    assert_eq!(vec!["done", "undone"], expand(aff, "done/2\n"));
+    assert_eq!(vec!["tie", "ties", "untie", "unties"], expand(aff, "tie/1\n"));
+    let affixes = Affixes::parse(aff.as_bytes(), &Encoding::Utf_8).unwrap();
+    assert_eq!(vec!["plain"], affixes.expand_entry("plain"));
+
+    // Aliases beyond the count declared by header get ignored
+    let aff = aff.replace("AF 2", "AF 1");
+    assert_eq!(vec!["tie", "ties", "untie", "unties"], expand(&aff, "tie/1\n"));
+    assert_eq!(vec!["done"], expand(&aff, "done/2\n"));
+}
+
+#[test]
+fn latin1() {
+    let aff = b"SET ISO8859-1\nSFX S Y 1\nSFX S 0 s .\n";
+    let dic = b"1\nv\xe9rit\xe9/S\n";
+    let words = hunspell::expand_from(&dic[..], &aff[..], &Encoding::Utf_8).unwrap();
+    assert_eq!(vec!["vérité", "vérités"], words);
+}
+
+#[test]
+fn load_dic_with_aff() {
+    let dir = std::env::temp_dir()
+        .join(format!("anagram-phrases-{}-hunspell", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dic = dir.join("en.dic");
+    std::fs::write(&dic, "3\nnewel/S\nwashout/S\nsky/S\n").unwrap();
+    std::fs::write(dir.join("en.aff"), AFF).unwrap();
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![dic.clone()],
+        max_phrase_words: 2,
+        ..Config::default()
+    };
+    let input_phrase: Vec<String> =
+        ["new", "south", "wales"].iter().map(ToString::to_string).collect();
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (map, _) = words::load_and_select(
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &[],
+    )
+    .unwrap();
+    let cache = words::Cache::init(&map);
+    let anagrams = search.add_cache(&cache).brute_force();
+    assert_eq!(2, anagrams.len());
+
+    let lexicon = words::preload(
+        &[Path::new(&dic)],
+        &Rules::builtin(&config.lang),
+        &config.encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    )
+    .unwrap();
+    assert_eq!(6, lexicon.values().flatten().count());
+    let solution = search::resolve(
+        &input_phrase,
+        &[],
+        &[],
+        &config,
+        &lexicon,
+        &Sources::default(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(anagrams.len(), solution.anagrams.len());
+    std::fs::remove_dir_all(dir).unwrap();
+}
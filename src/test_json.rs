@@ -0,0 +1,48 @@
+use crate::json;
+use crate::test_helpers::{new_south_wales, solutions};
+
+#[test]
+fn single_query() {
+    let singles = vec!["silent".to_string()];
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    json::write_to(&mut buffer, 3, &singles, &phrases).unwrap();
+    let expected = concat!(
+        r#"{"transpositions":["silent"],"anagrams":"#,
+        r#"[[["newel"],["washouts"]],[["ale","lea"],["snouts"],["whew"]]]}"#
+    );
+    // Exact text, as reading back is covered by `test_diff::json_round_trip`
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+}
+
+#[test]
+fn batch() {
+    let solutions = solutions();
+    let mut buffer: Vec<u8> = vec![];
+    json::write_batch_to(&mut buffer, 3, &solutions).unwrap();
+    let expected = concat!(
+        r#"[{"query":"listen","transpositions":["silent"],"anagrams":[]},"#,
+        r#"{"query":"new south wales","transpositions":[],"anagrams":"#,
+        r#"[[["newel"],["washouts"]],[["ale","lea"],["snouts"],["whew"]]]}]"#
+    );
+    assert_eq!(expected, String::from_utf8(buffer).unwrap());
+
+    // Empty batch is still an array:
+    let mut buffer: Vec<u8> = vec![];
+    json::write_batch_to(&mut buffer, 3, &[]).unwrap();
+    assert_eq!(b"[]", buffer.as_slice());
+}
+
+#[test]
+fn longer_than_max() {
+    let phrases = new_south_wales();
+    let mut buffer: Vec<u8> = vec![];
+    json::write_to(&mut buffer, 2, &[], &phrases).unwrap();
+    let results = json::read_from(buffer.as_slice()).unwrap();
+    assert_eq!(vec![phrases[1].clone(), phrases[0].clone()], results.anagrams);
+
+    let mut buffer: Vec<u8> = vec![];
+    json::write_batch_to(&mut buffer, 2, &solutions()).unwrap();
+    let batch: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(2, batch[1]["anagrams"].as_array().unwrap().len());
+}
@@ -1,4 +1,4 @@
-use crate::languages::{self, Language, Region, SHORT, UPCASE};
+use crate::languages::{self, Casing, Language, Region, Rules, SHORT, UPCASE};
 
 #[test]
 fn filters() {
@@ -24,6 +24,45 @@ fn filters() {
     // ES == Español, Spanish
     let short = SHORT.get(&Language::ES).unwrap();
     assert!(!languages::filter("y", short, upcase, false, false));
+
+    // IT == Italiano, Italian; PT == Português, Portuguese:
+    for lang in [Language::IT, Language::PT] {
+        let short = SHORT.get(&lang).unwrap();
+        assert!(!languages::filter("e", short, upcase, false, false));
+        assert!(languages::filter("y", short, upcase, false, false));
+    }
+
+    // Single letters beyond ASCII span multiple bytes of UTF-8:
+    // RU == Русский, Russian; UK == Українська, Ukrainian; EL == Ελληνικά
+    for (lang, kept, dropped) in
+        [(Language::RU, "и", "ж"), (Language::UK, "і", "ж"), (Language::EL, "ή", "ξ")]
+    {
+        let rules = Rules::builtin(&lang);
+        assert!(!rules.filter(kept, false, false));
+        assert!(rules.filter(kept, true, false));
+        assert!(rules.filter(dropped, false, false));
+        assert!(!rules.filter(&format!("{kept}{dropped}"), false, false));
+    }
+}
+
+#[test]
+fn capitalized_nouns() {
+    // DE == Deutsch, German
+    let rules = Rules::builtin(&Language::DE);
+    assert!(rules.capitalized_nouns);
+    assert!(!rules.filter("Hund", false, false));
+    assert!(!rules.filter("Hund", false, true));
+    assert!(rules.filter("x", false, false));
+    // German has no single letter words at all
+    assert!(rules.short.is_empty());
+    assert_eq!(2, rules.min_length);
+    assert!(rules.filter("o", false, false));
+    assert!(!rules.filter("zu", false, false));
+
+    let rules = Rules::builtin(&Language::NL);
+    assert!(!rules.capitalized_nouns);
+    assert!(!rules.filter("u", false, false));
+    assert!(rules.filter("Hond", false, true));
 }
 
 #[test]
@@ -40,8 +79,95 @@ fn lang() {
     assert_eq!(languages::parse_lang("en-us"), (Language::EN, Region::US));
     assert_eq!(languages::parse_lang("en_US"), (Language::EN, Region::US));
     assert_eq!(languages::parse_lang("en_GB"), (Language::EN, Region::GB));
+    assert_eq!(languages::parse_lang("pt_BR"), (Language::PT, Region::BR));
+    assert_eq!(languages::parse_lang("de-AT"), (Language::DE, Region::AT));
+    assert_eq!(languages::parse_lang("de_CH.UTF-8"), (Language::DE, Region::CH));
     assert_eq!(languages::parse_lang("en_UK"), (Language::EN, Region::GB));
+    assert_eq!(languages::parse_lang("de_DE.UTF-8"), (Language::DE, Region::DE));
+    assert_eq!(languages::parse_lang("it"), (Language::IT, Region::Any));
+    assert_eq!(languages::parse_lang("nl-BE"), (Language::NL, Region::BE));
+    assert_eq!(languages::parse_lang("PT"), (Language::PT, Region::Any));
     // HTTP header Accept-Language weighting gets silently ignored:
     assert_eq!(languages::parse_lang("en_CA;q=0.9"), (Language::EN, Region::CA));
     assert_eq!(languages::parse_lang("en-CA;q=0.7"), (Language::EN, Region::CA));
 }
+
+#[test]
+fn builtin_rules() {
+    let rules = Rules::builtin(&Language::EN);
+    assert_eq!(vec!["I", "a"], rules.short);
+    assert!(rules.filter("n", false, false));
+    assert!(!rules.filter("a", false, false));
+    assert!(!rules.filter("th", false, false));
+    let rules = Rules { min_length: 3, stopwords: vec!["the".into()], ..rules };
+    assert!(rules.filter("th", false, false));
+    assert!(!rules.filter("a", false, false));
+    assert!(rules.filter("the", false, false));
+    assert!(!rules.filter("then", false, false));
+    assert_eq!(
+        Rules::builtin(&Language::EN),
+        Rules::load(None, &Language::EN, &Region::Any).unwrap()
+    );
+}
+
+#[cfg(feature = "rules")]
+#[test]
+fn rules_file() {
+    use crate::error::AnagramError;
+
+    let dir = std::env::temp_dir();
+    let toml = dir.join(format!("anagram-phrases-{}-rules.toml", std::process::id()));
+    std::fs::write(
+        &toml,
+        r#"
+[en]
+min_length = 2
+stopwords = ["th"]
+
+[EN_GB]
+upcase = ["I", "Thames"]
+
+[FR]
+min_length = 9
+"#,
+    )
+    .unwrap();
+    let rules = Rules::load(Some(&toml), &Language::EN, &Region::UK).unwrap();
+    assert_eq!(vec!["I", "Thames"], rules.upcase);
+    assert_eq!(vec!["I", "a"], rules.short);
+    assert_eq!(2, rules.min_length);
+    assert!(!rules.filter("Thames", false, false));
+    assert!(rules.filter("th", false, false));
+    let rules = Rules::load(Some(&toml), &Language::EN, &Region::US).unwrap();
+    assert_eq!(vec!["I"], rules.upcase);
+    assert!(rules.filter("Thames", false, false));
+
+    let json = dir.join(format!("anagram-phrases-{}-rules.json", std::process::id()));
+    std::fs::write(&json, r#"{"ES": {"short": ["a", "o", "y"]}}"#).unwrap();
+    let rules = Rules::load(Some(&json), &Language::ES, &Region::Any).unwrap();
+    assert_eq!(vec!["a", "o", "y"], rules.short);
+    std::fs::write(&json, r#"{"ES": {"shorts": []}}"#).unwrap();
+    let invalid = Rules::load(Some(&json), &Language::ES, &Region::Any);
+    assert!(matches!(invalid, Err(AnagramError::RulesFile(_))));
+    std::fs::remove_file(toml).unwrap();
+    std::fs::remove_file(json).unwrap();
+}
+
+#[test]
+fn expansions() {
+    assert_eq!(&[('ß', "ss")], Language::DE.expansions());
+    assert!(Language::FR.expansions().contains(&('œ', "oe")));
+    assert!(Language::ES.expansions().is_empty());
+    let rules = Rules::builtin(&Language::NL);
+    assert_eq!(Language::NL.expansions(), rules.alphabet().expansions);
+}
+
+#[test]
+fn casing() {
+    assert_eq!(Casing::Unicode, Language::EN.casing());
+    assert_eq!("ılık", Language::TR.casing().lowercase("ILIK"));
+    assert_eq!("istanbul", Language::TR.casing().lowercase("İstanbul"));
+    assert_eq!("ilik", Language::EN.casing().lowercase("ILIK"));
+    assert_eq!("οδοσ", Language::EL.casing().lowercase("ΟΔΟΣ"));
+    assert_eq!(Language::TR, Language::from("tr"));
+}
@@ -2,25 +2,39 @@ use num_bigint::{BigUint, ToBigUint};
 use std::collections::BTreeMap;
 
 use crate::error::AnagramError;
+use crate::fold;
+use crate::languages::{Casing, Language, Normalization, Rules, Script};
 use crate::primes::*;
 
 #[test]
 fn uniques() {
-    assert_eq!(extract_unique_chars("this-is-that"), "thisa".to_string());
-    assert_eq!(extract_unique_chars("this is that"), "thisa".to_string());
-    assert_eq!(extract_unique_chars("This Is That"), "thisa".to_string());
-    assert_eq!(extract_unique_chars("My cat's hat"), "mycatsh".to_string());
     assert_eq!(
-        extract_unique_chars("Liberté, Égalité, Fraternité"),
+        extract_unique_chars("this-is-that", Alphabet::default()),
+        "thisa".to_string()
+    );
+    assert_eq!(
+        extract_unique_chars("this is that", Alphabet::default()),
+        "thisa".to_string()
+    );
+    assert_eq!(
+        extract_unique_chars("This Is That", Alphabet::default()),
+        "thisa".to_string()
+    );
+    assert_eq!(
+        extract_unique_chars("My cat's hat", Alphabet::default()),
+        "mycatsh".to_string()
+    );
+    assert_eq!(
+        extract_unique_chars("Liberté, Égalité, Fraternité", Alphabet::default()),
         "libertégafn".to_string()
     )
 }
 
 #[test]
 fn essentials() {
-    assert_eq!(essential_chars("Foo"), "foo".to_string());
-    assert_eq!(essential_chars("foo"), "foo".to_string());
-    assert_eq!(essential_chars("aaaaaaa"), "aaaaaaa".to_string());
+    assert_eq!(essential_chars("Foo", Alphabet::default()), "foo".to_string());
+    assert_eq!(essential_chars("foo", Alphabet::default()), "foo".to_string());
+    assert_eq!(essential_chars("aaaaaaa", Alphabet::default()), "aaaaaaa".to_string());
 }
 
 #[test]
@@ -62,14 +76,14 @@ fn product() {
             * 23
             * 71
             * 97;
-        let product = primes(word).unwrap();
+        let product = primes(word, Alphabet::default()).unwrap();
         assert_eq!(primes_product(&product).unwrap(), big.to_biguint().unwrap());
     }
     #[cfg(feature = "disable-u128")]
     {
         let word = "conductivity";
         let big: u64 = 5 * 47 * 43 * 7 * 73 * 5 * 71 * 23 * 79 * 23 * 71 * 97;
-        let product = primes(word).unwrap();
+        let product = primes(word, Alphabet::default()).unwrap();
         assert_eq!(primes_product(&product).unwrap(), big.to_biguint().unwrap());
     }
 }
@@ -77,11 +91,11 @@ fn product() {
 #[test]
 fn filtering() {
     let product: BigUint = 2u8.to_biguint().unwrap();
-    match filter_word("abc", "a", 1, &product) {
+    match filter_word("abc", "a", 1, &product, Alphabet::default()) {
         Err(AnagramError::WordTooLong) => {}
         other => panic!("expected: {} received: {:?}", AnagramError::WordTooLong, other),
     }
-    match filter_word("z", "a", 1, &product) {
+    match filter_word("z", "a", 1, &product, Alphabet::default()) {
         Err(AnagramError::MismatchedChars) => {}
         other => {
             panic!("expected: {} received: {:?}", AnagramError::MismatchedChars, other)
@@ -89,7 +103,7 @@ fn filtering() {
     }
 
     let product: BigUint = (2 * 3 * 5 * 101).to_biguint().unwrap();
-    match filter_word("zzz", "abcz", 4, &product) {
+    match filter_word("zzz", "abcz", 4, &product, Alphabet::default()) {
         Err(AnagramError::WordProductTooBig) => {}
         other => {
             panic!("expected: {} received: {:?}", AnagramError::WordProductTooBig, other)
@@ -116,9 +130,9 @@ fn positive_2() {
 }
 
 fn with_static_dictionary(input: &str, dictionary: &[&str], primes: &[u16]) {
-    let essential = essential_chars(input);
+    let essential = essential_chars(input, Alphabet::default());
     let input_length = essential.len();
-    let pattern = extract_unique_chars(input);
+    let pattern = extract_unique_chars(input, Alphabet::default());
     let product = primes.iter().fold(1, |acc, &x| acc * x as usize);
     match primes_product(primes) {
         Ok(input_product) => {
@@ -126,9 +140,13 @@ fn with_static_dictionary(input: &str, dictionary: &[&str], primes: &[u16]) {
             let mut map: PMap = BTreeMap::new();
             let mut wordlist: Vec<String> = vec![];
             for word in dictionary {
-                if let Ok(product) =
-                    filter_word(word, &pattern, input_length, &input_product)
-                {
+                if let Ok(product) = filter_word(
+                    word,
+                    &pattern,
+                    input_length,
+                    &input_product,
+                    Alphabet::default(),
+                ) {
                     map.entry(product)
                         .or_insert(Vec::with_capacity(1))
                         .push(word.to_string());
@@ -146,36 +164,231 @@ fn with_static_dictionary(input: &str, dictionary: &[&str], primes: &[u16]) {
 #[test]
 fn latin1() {
     // Within the ASCII range, accept only [a-z]
-    assert!(hash('\u{0060}').is_none());
-    assert!(hash('\u{0061}').is_some()); // `a`
-    assert!(hash('\u{006f}').is_some()); // `o`
-    assert!(hash('\u{007a}').is_some()); // `z`
-    assert!(hash('\u{007b}').is_none());
+    assert!(hash('\u{0060}', Script::Latin).is_none());
+    assert!(hash('\u{0061}', Script::Latin).is_some()); // `a`
+    assert!(hash('\u{006f}', Script::Latin).is_some()); // `o`
+    assert!(hash('\u{007a}', Script::Latin).is_some()); // `z`
+    assert!(hash('\u{007b}', Script::Latin).is_none());
 
-    assert!(hash('\u{00A0}').is_none()); // NBSP
+    assert!(hash('\u{00A0}', Script::Latin).is_none()); // NBSP
 
     // ISO-8859-1 has à=U+00E0 for lowercase
-    assert!(hash('\u{00e0}').is_some());
+    assert!(hash('\u{00e0}', Script::Latin).is_some());
     // ISO-8859-1 has ÿ=U+00FF for lowercase
-    assert!(hash('\u{00ff}').is_some());
+    assert!(hash('\u{00ff}', Script::Latin).is_some());
 }
 
 #[cfg(feature = "external-hasher")]
 #[test]
 fn latin_extended_a() {
     // Within the ASCII range, accept only [a-z]
-    assert!(hash('\u{0060}').is_none());
-    assert!(hash('\u{0061}').is_some()); // `a`
-    assert!(hash('\u{006f}').is_some()); // `o`
-    assert!(hash('\u{007a}').is_some()); // `z`
-    assert!(hash('\u{007b}').is_none());
+    assert!(hash('\u{0060}', Script::Latin).is_none());
+    assert!(hash('\u{0061}', Script::Latin).is_some()); // `a`
+    assert!(hash('\u{006f}', Script::Latin).is_some()); // `o`
+    assert!(hash('\u{007a}', Script::Latin).is_some()); // `z`
+    assert!(hash('\u{007b}', Script::Latin).is_none());
 
-    assert!(hash('\u{00a0}').is_none()); // NBSP
+    assert!(hash('\u{00a0}', Script::Latin).is_none()); // NBSP
 
     // https://en.wikipedia.org/wiki/Latin_Extended-A
     // ā 	Latin Small letter A with macron
-    assert!(hash('\u{0101}').is_some());
+    assert!(hash('\u{0101}', Script::Latin).is_some());
     // https://en.wikipedia.org/wiki/Latin_Extended-B
     // ƀ 	Latin Small Letter B with Stroke
-    assert!(hash('\u{0180}').is_some());
+    assert!(hash('\u{0180}', Script::Latin).is_some());
+}
+
+#[cfg(not(feature = "external-hasher"))]
+const CYRILLIC: Alphabet = Alphabet {
+    script: Script::Cyrillic,
+    expansions: &[],
+    casing: Casing::Unicode,
+    fold_diacritics: false,
+    normalization: Normalization::NFC,
+};
+
+#[cfg(not(feature = "external-hasher"))]
+#[test]
+fn cyrillic() {
+    // Cyrillic gets the smallest primes, followed by Latin
+    assert_eq!(hash('\u{0430}', Script::Cyrillic), Some(0)); // `а`
+    assert_eq!(hash('\u{044f}', Script::Cyrillic), Some(31)); // `я`
+    assert!(hash('\u{0456}', Script::Cyrillic).is_some()); // Ukrainian `і`
+    assert!(hash('\u{0491}', Script::Cyrillic).is_some()); // Ukrainian `ґ`
+    assert!(hash('\u{0410}', Script::Cyrillic).is_none()); // uppercase `А`
+    assert!(hash('\u{03b1}', Script::Cyrillic).is_none()); // Greek `α`
+    assert!(hash('\u{0430}', Script::Latin).is_none());
+    assert_ne!(hash('a', Script::Cyrillic), hash('\u{0430}', Script::Cyrillic));
+    assert!(hash('\u{00ff}', Script::Cyrillic).unwrap() < 200);
+
+    let essential = essential_chars("Автор", CYRILLIC);
+    let input_product = primes_product(&primes(&essential, CYRILLIC).unwrap());
+    let input_product = input_product.unwrap();
+    let pattern = extract_unique_chars("Автор", CYRILLIC);
+    for word in ["товар", "отвар"] {
+        let product =
+            filter_word(word, &pattern, essential.len(), &input_product, CYRILLIC);
+        assert_eq!(product.unwrap(), input_product);
+    }
+    assert!(
+        filter_word("вор", &pattern, essential.len(), &input_product, CYRILLIC).is_ok()
+    );
+}
+
+#[cfg(not(feature = "external-hasher"))]
+#[test]
+fn greek() {
+    // Greek gets the smallest primes, followed by Latin
+    assert_eq!(hash('\u{03ac}', Script::Greek), Some(0)); // `ά`
+    assert!(hash('\u{03b1}', Script::Greek).is_some()); // `α`
+    assert!(hash('\u{03c9}', Script::Greek).is_some()); // `ω`
+    assert!(hash('\u{03ce}', Script::Greek).is_some()); // `ώ`
+    assert!(hash('\u{0390}', Script::Greek).is_some()); // `ΐ`
+    assert!(hash('\u{0391}', Script::Greek).is_none()); // uppercase `Α`
+    assert!(hash('\u{0430}', Script::Greek).is_none()); // Cyrillic `а`
+    assert!(hash('\u{03b1}', Script::Latin).is_none());
+    assert!(hash('\u{00ff}', Script::Greek).unwrap() < 200);
+}
+
+#[cfg(not(feature = "external-hasher"))]
+#[test]
+fn scripts_injective() {
+    let latin1 =
+        ('a'..='z').chain('\u{00a1}'..='\u{00ff}').chain(['ğ', 'ı', 'ş', 'ĳ', 'œ']);
+    for (script, native) in [
+        (Script::Cyrillic, ('\u{0430}'..='\u{045f}').chain(['\u{0491}'])),
+        (Script::Greek, ('\u{03ac}'..='\u{03ce}').chain(['\u{0390}'])),
+    ] {
+        let mut seen = std::collections::BTreeSet::new();
+        for ch in native.chain(latin1.clone()) {
+            let index = hash(ch, script).unwrap();
+            assert!(index < 200);
+            assert!(seen.insert(index), "{ch} collides within {script:?}");
+        }
+    }
+}
+
+#[test]
+fn folding_diacritics() {
+    let folded = Alphabet { fold_diacritics: true, ..Alphabet::default() };
+    assert_eq!(essential_chars("Vérité", folded), "verite".to_string());
+    assert_eq!(extract_unique_chars("Vérité", folded), "verit".to_string());
+    assert_eq!(essential_chars("Vérité", Alphabet::default()), "vérité".to_string());
+    assert_eq!(primes("é", folded).unwrap(), primes("e", folded).unwrap());
+    assert_ne!(
+        primes("é", Alphabet::default()).unwrap(),
+        primes("e", Alphabet::default()).unwrap()
+    );
+
+    let input = "élan";
+    let essential = essential_chars(input, folded);
+    let pattern = extract_unique_chars(input, folded);
+    let input_product = primes_product(&primes(&essential, folded).unwrap()).unwrap();
+    for word in ["lane", "Léna", "lean"] {
+        let product =
+            filter_word(word, &pattern, essential.len(), &input_product, folded);
+        assert_eq!(product.unwrap(), input_product);
+    }
+    let exact = Alphabet::default();
+    let essential = essential_chars(input, exact);
+    let pattern = extract_unique_chars(input, exact);
+    let input_product = primes_product(&primes(&essential, exact).unwrap()).unwrap();
+    let product = filter_word("lane", &pattern, essential.len(), &input_product, exact);
+    assert!(matches!(product, Err(AnagramError::MismatchedChars)));
+
+    // Decomposition accommodates any script; strokes get folded too
+    assert_eq!('α', fold::base_letter('ά', Casing::Unicode));
+    assert_eq!('е', fold::base_letter('ё', Casing::Unicode));
+    assert_eq!('o', fold::base_letter('ø', Casing::Unicode));
+    assert_eq!('L', fold::base_letter('Ł', Casing::Unicode));
+    assert_eq!('æ', fold::base_letter('æ', Casing::Unicode));
+    assert_eq!("Ocean a Lodz", fold::diacritics("Océan à Łódź", Casing::Unicode));
+}
+
+#[test]
+fn normalization() {
+    let composed = "\u{00e9}lan"; // élan
+    let decomposed = "e\u{0301}lan";
+    let nfc = Alphabet::default();
+    let nfd = Alphabet { normalization: Normalization::NFD, ..nfc };
+    assert_eq!(nfc.normalize(decomposed), composed);
+    assert_eq!(nfd.normalize(composed), decomposed);
+    assert!(matches!(nfc.normalize(composed), std::borrow::Cow::Borrowed(_)));
+    assert_eq!(essential_chars(decomposed, nfc), composed);
+    assert_eq!(extract_unique_chars(decomposed, nfc), "élan");
+    // Accents remain significant even when retaining words as NFD
+    assert_eq!(essential_chars(decomposed, nfd), composed);
+    let essential = essential_chars(decomposed, nfd);
+    assert_ne!(primes(&essential, nfd).unwrap(), primes("elan", nfd).unwrap());
+
+    // Query and dictionary word in different forms
+    for alphabet in [nfc, nfd] {
+        let essential = essential_chars(composed, alphabet);
+        let pattern = extract_unique_chars(composed, alphabet);
+        let product = primes_product(&primes(&essential, alphabet).unwrap()).unwrap();
+        let word = "le\u{0301}an"; // léan
+        let matched = filter_word(word, &pattern, essential.len(), &product, alphabet);
+        assert_eq!(matched.unwrap(), product);
+    }
+}
+
+#[test]
+fn expansions() {
+    let german = Alphabet { expansions: &[('ß', "ss")], ..Alphabet::default() };
+    assert_eq!(essential_chars("Straße", german), "strasse".to_string());
+    assert_eq!(extract_unique_chars("Straße", german), "strae".to_string());
+    assert_eq!(primes("ß", german).unwrap(), primes("ss", german).unwrap());
+    assert_eq!(essential_chars("Straße", Alphabet::default()), "straße".to_string());
+
+    let essential = essential_chars("Strasse", german);
+    let pattern = extract_unique_chars("Strasse", german);
+    let product = primes_product(&primes(&essential, german).unwrap()).unwrap();
+    let matched = filter_word("Straße", &pattern, essential.len(), &product, german);
+    assert_eq!(matched.unwrap(), product);
+
+    // Œ lowercases to œ prior to expanding
+    let french = Alphabet { expansions: &[('æ', "ae"), ('œ', "oe")], ..german };
+    assert_eq!(essential_chars("Cœur", french), "coeur".to_string());
+    assert_eq!(essential_chars("ŒUVRE", french), "oeuvre".to_string());
+    assert!(matches!(french.expand("coeur"), std::borrow::Cow::Borrowed(_)));
+}
+
+#[cfg(not(feature = "external-hasher"))]
+#[test]
+fn ligatures_without_expansions() {
+    // IT == Italiano, Italian; ligatures remain letters of their own
+    let italian = Rules::builtin(&Language::IT).alphabet();
+    assert!(italian.expansions.is_empty());
+    for word in ["cœur", "Œuvre", "ĳs", "Æther"] {
+        let essential = essential_chars(word, italian);
+        assert!(primes(&essential, italian).is_ok(), "{word}");
+    }
+    let ligature = primes(&essential_chars("cœur", italian), italian).unwrap();
+    assert_ne!(primes("coeur", italian).unwrap(), ligature);
+}
+
+#[test]
+fn casing() {
+    let turkish = Alphabet { casing: Casing::Turkic, ..Alphabet::default() };
+    assert_eq!(essential_chars("IŞIK", turkish), "ışık".to_string());
+    assert_eq!(essential_chars("İzmir", turkish), "izmir".to_string());
+    assert_eq!(essential_chars("IŞIK", Alphabet::default()), "işik".to_string());
+    let essential = essential_chars("Kış", turkish);
+    let pattern = extract_unique_chars("Kış", turkish);
+    let product = primes_product(&primes(&essential, turkish).unwrap()).unwrap();
+    assert!(filter_word("KIŞ", &pattern, essential.len(), &product, turkish).is_ok());
+    assert!(filter_word("kiş", &pattern, essential.len(), &product, turkish).is_err());
+
+    // Folding keeps dotless ı distinct from i only for Turkish
+    let folded = Alphabet { fold_diacritics: true, ..turkish };
+    assert_eq!(essential_chars("IŞIK", folded), "ısık".to_string());
+    let folded = Alphabet { casing: Casing::Unicode, ..folded };
+    assert_eq!(essential_chars("ışık", folded), "isik".to_string());
+
+    let greek = Alphabet { script: Script::Greek, casing: Casing::Greek, ..turkish };
+    assert_eq!(essential_chars("ΟΔΟΣ", greek), "οδοσ".to_string());
+    assert_eq!(extract_unique_chars("σας", greek), "σα".to_string());
+    let unicode = Alphabet { casing: Casing::Unicode, ..greek };
+    assert_eq!(essential_chars("ΟΔΟΣ", unicode), "οδος".to_string());
+    assert_eq!(extract_unique_chars("σας", unicode), "σας".to_string());
 }
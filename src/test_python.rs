@@ -0,0 +1,56 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::wrap_pymodule;
+
+use crate::python::*;
+
+#[test]
+fn module() {
+    let path = std::env::temp_dir()
+        .join(format!("anagram-phrases-{}-python.txt", std::process::id()));
+    std::fs::write(&path, "enlist\nlisten\nsilent\nnewel\nnewels\nwashout\nwashouts\n")
+        .unwrap();
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let locals = PyDict::new(py);
+        locals.set_item("ap", wrap_pymodule!(anagram_phrases)(py)).unwrap();
+        locals.set_item("path", &path).unwrap();
+        py.run(
+            cr#"
+config = ap.Config(lang="en", dict_file_paths=[path])
+lexicon = ap.preload(config)
+assert len(lexicon) == 5, len(lexicon)
+
+search = ap.Search(["tinsel"], config)
+assert search.transpositions(lexicon) == ["enlist", "listen", "silent"]
+assert search.transpositions(lexicon, exclude=["listen"]) == ["enlist", "silent"]
+
+search = ap.Search(["new", "south", "wales"], config)
+expected = [[["newel"], ["washouts"]], [["newels"], ["washout"]]]
+assert sorted(search.brute_force(lexicon, max_duration=9.0)) == expected
+assert sorted(search.stream(lexicon)) == expected
+assert next(search.stream(lexicon)) in expected
+
+selected, singles = ap.load_and_select(search, exclude=["newel"])
+assert singles == []
+assert search.brute_force(selected) == [[["newels"], ["washout"]]]
+
+try:
+    ap.Config(lang="xx")
+    assert False
+except ap.AnagramPhrasesError:
+    pass
+
+try:
+    ap.Config(multiword_cost=0)
+    assert False
+except ValueError:
+    pass
+"#,
+            None,
+            Some(&locals),
+        )
+        .unwrap();
+    });
+    std::fs::remove_file(path).unwrap();
+}
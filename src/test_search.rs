@@ -17,6 +17,19 @@ static EN_CA_DICT_FILES: LazyLock<Vec<PathBuf>> =
 static EN_US_DICT_FILES: LazyLock<Vec<PathBuf>> =
     LazyLock::new(|| vec![PathBuf::from("third-party/en_US/SCOWL-wl/words.txt")]);
 
+// Hunspell dictionaries get expanded via sibling `.aff` file
+static DE_DE_DICT_FILES: LazyLock<Vec<PathBuf>> =
+    LazyLock::new(|| vec![PathBuf::from("third-party/de_DE/de_DE_frami.dic")]);
+
+static IT_IT_DICT_FILES: LazyLock<Vec<PathBuf>> =
+    LazyLock::new(|| vec![PathBuf::from("third-party/it_IT/it_IT.dic")]);
+
+static NL_NL_DICT_FILES: LazyLock<Vec<PathBuf>> =
+    LazyLock::new(|| vec![PathBuf::from("third-party/nl_NL/nl_NL.dic")]);
+
+static PT_PT_DICT_FILES: LazyLock<Vec<PathBuf>> =
+    LazyLock::new(|| vec![PathBuf::from("third-party/pt_PT/pt_PT.dic")]);
+
 /// One of the inaugural states of Australia was New South Wales.
 #[test]
 fn en_au_new_south_wales_two_words() {
@@ -534,6 +547,53 @@ fn canary_three_words() {
     anagrams(max_phrase_words, input_phrase, word_list_files, expected, false, true);
 }
 
+/// German nouns are capitalized yet retained, such as "Regen" (rain).
+#[test]
+fn de_de_regen_transpositions() {
+    transpositions(Language::DE, &DE_DE_DICT_FILES, "Regen", &["Regen", "gerne"]);
+}
+
+#[test]
+fn it_it_amore_transpositions() {
+    transpositions(Language::IT, &IT_IT_DICT_FILES, "amore", &["amore", "ramo"]);
+}
+
+#[test]
+fn nl_nl_mast_transpositions() {
+    transpositions(Language::NL, &NL_NL_DICT_FILES, "mast", &["mast", "stam"]);
+}
+
+#[test]
+fn pt_pt_amor_transpositions() {
+    transpositions(Language::PT, &PT_PT_DICT_FILES, "amor", &["amor", "ramo"]);
+}
+
+/// Hunspell word lists contain too many inflections for keeping full
+/// results here, so confirm only that `expected` are among them.
+fn transpositions(
+    lang: Language, word_list_files: &[PathBuf], input_phrase: &str, expected: &[&str],
+) {
+    for f in word_list_files {
+        assert!(std::fs::exists(f).expect("Word list file not found"));
+    }
+
+    let input_phrase = [input_phrase.to_string()];
+    let config =
+        Config { lang, dict_file_paths: word_list_files.to_vec(), ..Config::default() };
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (_dict, singles) = words::load_and_select(
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &[],
+    )
+    .unwrap();
+    for word in expected {
+        assert!(singles.iter().any(|w| w == word), "{word} not within {singles:?}");
+    }
+}
+
 fn anagrams(
     max_phrase_words: usize, input_phrase: &str, word_list_files: &[PathBuf],
     expected: Vec<Vec<Vec<&str>>>, elided: bool, streaming: bool,
@@ -0,0 +1,106 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::languages::Language;
+use crate::search::{self, Search};
+use crate::sources;
+use crate::test_helpers::word_list;
+use crate::words;
+
+#[test]
+fn split_tag() {
+    let tagged = sources::split_tag(Path::new("slang=lists/slang.txt.gz"));
+    assert_eq!(("slang".to_string(), PathBuf::from("lists/slang.txt.gz")), tagged);
+    let untagged = sources::split_tag(Path::new("lists/slang.txt.gz"));
+    assert_eq!(("slang".to_string(), PathBuf::from("lists/slang.txt.gz")), untagged);
+    let stdin = sources::split_tag(Path::new(words::STDIN_PATH));
+    assert_eq!(sources::STDIN_TAG, stdin.0);
+    assert_eq!(Ok(("slang".to_string(), 1)), sources::parse_tagged::<usize>("slang=1"));
+    assert!(sources::parse_tagged::<usize>("slang").is_err());
+}
+
+#[test]
+fn tagged_weighted_limited() {
+    let core = word_list("core", &["man", "retinas"]);
+    let slang = word_list("slang", &["stainer", "retinas"]);
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![
+            PathBuf::from(format!("core={}", core.display())),
+            PathBuf::from(format!("slang={}", slang.display())),
+        ],
+        max_phrase_words: 3,
+        source_weights: vec![("slang".to_string(), 0.5)],
+        ..Config::default()
+    };
+    let input_phrase = ["retinas".to_string(), "man".to_string()];
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let load = |config: &Config| {
+        words::load_and_select_sources(
+            config,
+            &search.pattern,
+            &search.essential,
+            &search.primes_product,
+            &[],
+        )
+        .unwrap()
+    };
+    let (dict, _singles, sources) = load(&config);
+    assert_eq!(2, sources.tags.len());
+    assert_eq!(Some("core"), sources.tag("retinas"));
+    assert_eq!(Some("slang"), sources.tag("stainer"));
+
+    let man = vec!["man".to_string()];
+    let cache = words::Cache::with_sources(&dict, &sources);
+    let mut anagrams = search.add_cache(&cache).brute_force();
+    let retinas = vec![man.clone(), vec!["retinas".to_string()]];
+    let stainer = vec![man.clone(), vec!["stainer".to_string()]];
+    anagrams.reverse();
+    sources.rank(&mut anagrams);
+    assert_eq!(vec![retinas.clone(), stainer], anagrams);
+    assert_eq!(0.5, sources.weight(&anagrams[1]));
+    let provenance = sources.provenance(&[], &anagrams);
+    assert_eq!(Some(&"core".to_string()), provenance.get("man"));
+    assert_eq!(Some(&"slang".to_string()), provenance.get("stainer"));
+
+    let limited = Config { source_limits: vec![("slang".to_string(), 0)], ..config };
+    let (dict, _singles, sources) = load(&limited);
+    let cache = words::Cache::with_sources(&dict, &sources);
+    let search = Search::query(&input_phrase, &[], &limited).unwrap();
+    let anagrams = search.add_cache(&cache).brute_force();
+    std::fs::remove_file(core).unwrap();
+    std::fs::remove_file(slang).unwrap();
+    assert_eq!(vec![retinas], anagrams);
+}
+
+#[test]
+fn preloaded_weighted_limited() {
+    let core = word_list("preload-core", &["man", "retinas"]);
+    let slang = word_list("preload-slang", &["stainer", "retinas"]);
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![
+            PathBuf::from(format!("core={}", core.display())),
+            PathBuf::from(format!("slang={}", slang.display())),
+        ],
+        max_phrase_words: 3,
+        source_weights: vec![("slang".to_string(), 0.5)],
+        ..Config::default()
+    };
+    let input_phrase = ["stainer".to_string(), "man".to_string()];
+    let resolve = |config: &Config| {
+        let (lexicon, sources) = words::preload_sources(config, false).unwrap();
+        search::resolve(&input_phrase, &[], &[], config, &lexicon, &sources, None, None)
+            .unwrap()
+    };
+    let man = vec!["man".to_string()];
+    let retinas = vec![man.clone(), vec!["retinas".to_string()]];
+    let stainer = vec![man, vec!["stainer".to_string()]];
+    assert_eq!(vec![retinas.clone(), stainer], resolve(&config).anagrams);
+
+    let limited = Config { source_limits: vec![("slang".to_string(), 0)], ..config };
+    let anagrams = resolve(&limited).anagrams;
+    std::fs::remove_file(core).unwrap();
+    std::fs::remove_file(slang).unwrap();
+    assert_eq!(vec![retinas], anagrams);
+}
@@ -0,0 +1,61 @@
+use rusqlite::Connection;
+
+use crate::primes::Alphabet;
+use crate::sqlite;
+use crate::test_helpers::new_south_wales;
+
+#[test]
+fn normalized_tables() {
+    let singles = vec!["silent".to_string()];
+    let phrases = new_south_wales();
+    let mut conn = Connection::open_in_memory().unwrap();
+    let alphabet = Alphabet::default();
+    sqlite::write_to(&mut conn, "listen", "EN", alphabet, 3, &singles, &[]).unwrap();
+    let query = "new south wales";
+    sqlite::write_to(&mut conn, query, "EN", alphabet, 3, &[], &phrases).unwrap();
+
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(2, count("SELECT count(*) FROM queries"));
+    assert_eq!(3, count("SELECT count(*) FROM phrases"));
+    assert_eq!(6, count("SELECT count(*) FROM terms"));
+    assert_eq!(7, count("SELECT count(*) FROM words"));
+    assert_eq!(7, count("SELECT count(*) FROM alternatives"));
+    assert_eq!(
+        1,
+        count(
+            "SELECT count(*) FROM phrases
+             JOIN terms ON terms.phrase_id = phrases.id
+             JOIN alternatives ON alternatives.term_id = terms.id
+             JOIN words ON words.id = alternatives.word_id
+             WHERE words.word = 'lea'"
+        )
+    );
+
+    let mut stmt = conn
+        .prepare("SELECT query, kind, word_count, letters, score, phrase FROM results")
+        .unwrap();
+    let rows: Vec<(String, String, usize, usize, usize, String)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    let expected = vec![
+        ("listen", "transposition", 1, 6, 36, "silent"),
+        ("new south wales", "anagram", 3, 13, 61, "ale|lea snouts whew"),
+        ("new south wales", "anagram", 2, 13, 89, "newel washouts"),
+    ];
+    let expected: Vec<(String, String, usize, usize, usize, String)> = expected
+        .into_iter()
+        .map(|(q, k, n, l, s, p)| (q.into(), k.into(), n, l, s, p.into()))
+        .collect();
+    assert_eq!(expected, rows);
+}
@@ -0,0 +1,472 @@
+use clap::Parser;
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use crate::blocklist::Blocklist;
+use crate::config::Config;
+use crate::fold;
+use crate::languages::{Casing, Encoding, Language, Normalization, Rules};
+use crate::search::{self, Search};
+use crate::sources::Sources;
+use crate::test_helpers::word_list;
+use crate::words::{self, Dedup};
+
+const WORDS: &[&str] = &[
+    "a", "ah", "ale", "aeons", "ha", "lea", "lust", "newel", "newels", "sale", "seal",
+    "slut", "snout", "snouts", "tuneless", "washout", "washouts", "whew", "wow", "Wales",
+    "zebra",
+];
+
+#[test]
+fn preload_then_select() {
+    let path = word_list("select", WORDS);
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![path.clone()],
+        max_phrase_words: 3,
+        ..Config::default()
+    };
+    let input_phrase: Vec<String> =
+        ["new", "south", "wales"].iter().map(ToString::to_string).collect();
+    let exclude = vec!["slut".to_string()];
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (expected, expected_singles) = words::load_and_select(
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &exclude,
+    )
+    .unwrap();
+
+    let lexicon = words::preload(
+        &[Path::new(&path)],
+        &Rules::builtin(&config.lang),
+        &config.encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    )
+    .unwrap();
+    assert!(lexicon.values().flatten().any(|w| w == "zebra"));
+    let (map, singles) = words::select(&lexicon, &search.primes_product, &exclude);
+    assert_eq!(expected, map);
+    assert_eq!(expected_singles, singles);
+
+    let solution = search::resolve(
+        &input_phrase,
+        &[],
+        &exclude,
+        &config,
+        &lexicon,
+        &Sources::default(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!("new south wales", solution.query);
+    assert!(solution.transpositions.is_empty());
+    assert_eq!(6, solution.anagrams.len());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn from_reader_with_step_budget() {
+    let config = Config { lang: Language::EN, max_phrase_words: 3, ..Config::default() };
+    let input_phrase: Vec<String> =
+        ["new", "south", "wales"].iter().map(ToString::to_string).collect();
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let bytes = WORDS.join("\n");
+    let (expected, _) = words::load_and_select_from(
+        bytes.as_bytes(),
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &[],
+    )
+    .unwrap();
+    let lexicon = words::preload_from(
+        bytes.as_bytes(),
+        &Rules::builtin(&config.lang),
+        &config.encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+    )
+    .unwrap();
+    let (map, _) = words::select(&lexicon, &search.primes_product, &[]);
+    assert_eq!(expected, map);
+
+    let cache = words::Cache::init(&map);
+    let mut visited = vec![];
+    let anagrams = search.add_cache(&cache).brute_force_with(|phrase| {
+        visited.push(phrase);
+        true
+    });
+    assert_eq!(anagrams.len(), visited.len());
+    assert!(anagrams.len() > 1);
+
+    let mut count = 0;
+    let first = search.add_cache(&cache).brute_force_with(|_| {
+        count += 1;
+        false
+    });
+    assert_eq!((1, 1), (count, first.len()));
+
+    let budget =
+        search.add_cache(&cache).with_max_steps(Some(1)).brute_force_with(|_| true);
+    assert!(budget.len() < anagrams.len());
+
+    let cancel = AtomicBool::new(true);
+    let cancelled =
+        search.add_cache(&cache).with_cancel(Some(&cancel)).brute_force_with(|_| true);
+    assert!(cancelled.len() < anagrams.len());
+}
+
+/// Compare word list compressed via `compress` with uncompressed one
+#[allow(dead_code)]
+fn compressed(name: &str, compress: impl FnOnce(&[u8]) -> Vec<u8>) {
+    let plain = word_list(name, WORDS);
+    let path = plain.with_extension(name);
+    std::fs::write(&path, compress(WORDS.join("\n").as_bytes())).unwrap();
+    let lang = Language::EN;
+    let encoding = Default::default();
+    let expected = words::preload(
+        &[Path::new(&plain)],
+        &Rules::builtin(&lang),
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    )
+    .unwrap();
+    let map = words::preload(
+        &[Path::new(&path)],
+        &Rules::builtin(&lang),
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    );
+    std::fs::remove_file(plain).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(expected, map.unwrap());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip() {
+    use std::io::Write;
+    compressed("gz", |bytes| {
+        let mut encoder = libflate::gzip::Encoder::new(vec![]).unwrap();
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().into_result().unwrap()
+    });
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn xz() {
+    use std::io::Read;
+    compressed("xz", |bytes| {
+        let mut compressed = vec![];
+        xz2::read::XzEncoder::new(bytes, 6).read_to_end(&mut compressed).unwrap();
+        compressed
+    });
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    compressed("zst", |bytes| zstd::encode_all(bytes, 0).unwrap());
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn compression_not_enabled() {
+    let path = word_list("gzip", &[]);
+    std::fs::write(&path, [0x1f, 0x8b, 0x08]).unwrap();
+    let result = words::open_file(&path);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(
+        result,
+        Err(crate::error::AnagramError::CompressionNotEnabled("gzip"))
+    ));
+}
+
+#[test]
+fn stdin() {
+    let stdin = || std::io::Cursor::new(b"sale\nseal\n".to_vec());
+    let path = Path::new(words::STDIN_PATH);
+    let (reader, encoding) =
+        words::open_with_stdin(path, &Encoding::Iso_8859_1, stdin).unwrap();
+    assert_eq!(Encoding::Iso_8859_1, encoding);
+    let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+    assert_eq!(vec!["sale", "seal"], lines);
+
+    // Other paths never read stdin:
+    let path = word_list("stdin", &["slut"]);
+    let unread = || -> std::io::Cursor<Vec<u8>> { unreachable!() };
+    let (reader, _) = words::open_with_stdin(&path, &Encoding::Utf_8, unread).unwrap();
+    let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+    assert_eq!(vec!["slut"], lines);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn directory_of_word_lists() {
+    let dir = std::env::temp_dir()
+        .join(format!("anagram-phrases-{}-directory", std::process::id()));
+    std::fs::create_dir_all(dir.join("subdirectory")).unwrap();
+    let (first, second) = WORDS.split_at(WORDS.len() / 2);
+    std::fs::write(dir.join("words.20"), second.join("\n")).unwrap();
+    std::fs::write(dir.join("words.10"), first.join("\n")).unwrap();
+    std::fs::write(dir.join("words.aff"), "SET UTF-8\n").unwrap();
+    std::fs::write(dir.join(".hidden"), "zzz\n").unwrap();
+
+    let files = words::dict_files(&[&dir], None).unwrap();
+    assert_eq!(vec![dir.join("words.10"), dir.join("words.20")], files);
+    let glob = glob::Pattern::new("*.1?").unwrap();
+    let files = words::dict_files(&[&dir], Some(&glob)).unwrap();
+    assert_eq!(vec![dir.join("words.10")], files);
+
+    let plain = word_list("directory", WORDS);
+    let lang = Language::EN;
+    let encoding = Default::default();
+    let expected = words::preload(
+        &[Path::new(&plain)],
+        &Rules::builtin(&lang),
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    )
+    .unwrap();
+    let map = words::preload(
+        &[Path::new(&dir)],
+        &Rules::builtin(&lang),
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+        false,
+    );
+    std::fs::remove_file(plain).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    assert_eq!(expected, map.unwrap());
+}
+
+#[test]
+fn duplicates_across_unsorted_word_lists() {
+    let first = word_list("dedup-1", &["seal", "sale", "Seal", "élan", "seal"]);
+    let second = word_list("dedup-2", &["elan", "sale", "lea", "SALE"]);
+    let paths = [Path::new(&first), Path::new(&second)];
+    let lang = Language::Any;
+    let encoding = Default::default();
+    let words = |dedup| -> Vec<String> {
+        let map = words::preload(
+            &paths,
+            &Rules::builtin(&lang),
+            &encoding,
+            false,
+            false,
+            dedup,
+            &Blocklist::default(),
+            false,
+        );
+        let mut words: Vec<String> = map.unwrap().into_values().flatten().collect();
+        words.sort();
+        words
+    };
+    assert_eq!(
+        vec!["SALE", "Seal", "elan", "lea", "sale", "seal", "élan"],
+        words(Dedup::default())
+    );
+    let case = Dedup { case: true, ..Dedup::default() };
+    assert_eq!(vec!["elan", "lea", "sale", "seal", "élan"], words(case));
+    let diacritics = Dedup { diacritics: true, ..Dedup::default() };
+    assert_eq!(vec!["SALE", "Seal", "lea", "sale", "seal", "élan"], words(diacritics));
+
+    // First spelling loaded wins regardless of whether it matches query
+    let config = Config {
+        lang,
+        dict_file_paths: vec![first.clone(), second.clone()],
+        dedup_diacritics: true,
+        ..Config::default()
+    };
+    let transpositions = |query: &str| {
+        let input_phrase = [query.to_string()];
+        let search = Search::query(&input_phrase, &[], &config).unwrap();
+        let (pattern, essential, product) =
+            (&search.pattern, &search.essential, &search.primes_product);
+        words::load_and_select(&config, pattern, essential, product, &[]).unwrap().1
+    };
+    assert_eq!(vec!["élan".to_string()], transpositions("nalé"));
+    assert!(transpositions("lane").is_empty());
+    std::fs::remove_file(first).unwrap();
+    std::fs::remove_file(second).unwrap();
+
+    // Spellings rejected by filters of language never win, such as
+    // capitalized "Bill" for English
+    let names = word_list("dedup-filtered", &["Bill", "bill", "it"]);
+    let config = Config {
+        lang: Language::EN,
+        dict_file_paths: vec![names.clone()],
+        dedup_case: true,
+        ..Config::default()
+    };
+    let input_phrase = ["bill".to_string()];
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (pattern, essential, product) =
+        (&search.pattern, &search.essential, &search.primes_product);
+    let (_, singles) =
+        words::load_and_select(&config, pattern, essential, product, &[]).unwrap();
+    assert_eq!(vec!["bill"], singles);
+    let (lexicon, _) = words::preload_sources(&config, false).unwrap();
+    let mut all: Vec<&String> = lexicon.values().flatten().collect();
+    all.sort();
+    assert_eq!(vec!["bill", "it"], all);
+    std::fs::remove_file(names).unwrap();
+
+    assert_eq!("Elan naive", fold::diacritics("Élan naïve", Casing::Unicode));
+
+    // Lowercase per language, where Turkish I is dotless ı
+    let turkish = Dedup { case: true, casing: Language::TR.casing(), ..Dedup::default() };
+    assert_eq!(turkish.key("IŞIK"), turkish.key("ışık"));
+    assert_ne!(turkish.key("ISIK"), turkish.key("isik"));
+    assert_eq!(case.key("ISIK"), case.key("isik"));
+}
+
+#[test]
+fn multiword_lexemes() {
+    let bytes = "retinas\nin tears\nstainer\nman\n";
+    let lang = Language::EN;
+    let encoding = Default::default();
+    let lexicon = words::preload_from(
+        bytes.as_bytes(),
+        &Rules::builtin(&lang),
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+    )
+    .unwrap();
+    let cache = words::Cache::init(&lexicon);
+    assert_eq!(vec![1, 2, 1], cache.word_counts);
+    assert_eq!(vec!["in tears".to_string()], *cache.alternatives[1]);
+
+    let input_phrase = ["retinas".to_string(), "man".to_string()];
+    let config = Config { lang, max_phrase_words: 2, ..Config::default() };
+    let resolve = |config: &Config| {
+        search::resolve(
+            &input_phrase,
+            &[],
+            &[],
+            config,
+            &lexicon,
+            &Sources::default(),
+            None,
+            None,
+        )
+        .unwrap()
+    };
+    let retinas = vec![vec!["man".to_string()], vec!["retinas".into(), "stainer".into()]];
+    let in_tears = vec![vec!["in tears".to_string()], vec!["man".to_string()]];
+    assert_eq!(vec![retinas.clone()], resolve(&config).anagrams);
+
+    let cheap = Config { multiword_cost: Some(1), ..config.clone() };
+    assert_eq!(vec![in_tears.clone(), retinas.clone()], resolve(&cheap).anagrams);
+    let roomy = Config { max_phrase_words: 3, ..config.clone() };
+    assert_eq!(vec![in_tears, retinas.clone()], resolve(&roomy).anagrams);
+    let excluded = Config { no_multiword: true, ..roomy };
+    assert_eq!(vec![retinas], resolve(&excluded).anagrams);
+
+    let input_phrase = ["in".to_string(), "tears".to_string()];
+    let config = Config { max_phrase_words: 1, ..excluded };
+    let solution = search::resolve(
+        &input_phrase,
+        &[],
+        &[],
+        &config,
+        &lexicon,
+        &Sources::default(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(vec!["retinas", "stainer"], solution.transpositions);
+
+    // Costing nothing would allow phrases of unlimited words
+    let parse =
+        |cost| Config::try_parse_from(["anagram-phrases", "--multiword-cost", cost]);
+    assert_eq!(Some(2), parse("2").unwrap().multiword_cost);
+    assert!(parse("0").is_err());
+}
+
+#[test]
+fn normalized_word_lists() {
+    // Same word precomposed and decomposed, followed by another decomposed
+    let bytes = "\u{00e9}lan\ne\u{0301}lan\nle\u{0301}an\n";
+    let lang = Language::FR;
+    let encoding = Default::default();
+    let rules = Rules::builtin(&lang);
+    let lexicon = words::preload_from(
+        bytes.as_bytes(),
+        &rules,
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+    )
+    .unwrap();
+    assert_eq!(1, lexicon.len());
+    let words: Vec<&String> = lexicon.values().flatten().collect();
+    assert_eq!(vec!["léan", "élan"], words);
+
+    let nfd = Rules { normalization: Normalization::NFD, ..rules };
+    let lexicon = words::preload_from(
+        bytes.as_bytes(),
+        &nfd,
+        &encoding,
+        false,
+        false,
+        Dedup::default(),
+        &Blocklist::default(),
+    )
+    .unwrap();
+    let words: Vec<&String> = lexicon.values().flatten().collect();
+    assert_eq!(vec!["e\u{0301}lan", "le\u{0301}an"], words);
+
+    // Excluding precomposed "élan" also excludes its decomposed form
+    let exclude = ["\u{00e9}lan".to_string()];
+    let product = lexicon.keys().next().unwrap();
+    let (_, singles) = words::select(&lexicon, product, &exclude);
+    assert_eq!(vec!["le\u{0301}an"], singles);
+    let config = Config { lang, normalization: Normalization::NFD, ..Config::default() };
+    let input_phrase = ["lane\u{0301}".to_string()];
+    let search = Search::query(&input_phrase, &[], &config).unwrap();
+    let (_, singles) = words::load_and_select_from(
+        bytes.as_bytes(),
+        &config,
+        &search.pattern,
+        &search.essential,
+        &search.primes_product,
+        &exclude,
+    )
+    .unwrap();
+    assert_eq!(vec!["le\u{0301}an"], singles);
+}
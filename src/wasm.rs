@@ -0,0 +1,120 @@
+//! JavaScript bindings via WebAssembly for solving anagrams within a
+//! browser, without any round-trip to a server.
+//!
+//! Build for `wasm32-unknown-unknown` with feature `wasm`; e.g.,
+//! `wasm-pack build --target web -- --features wasm`
+//!
+//! There is no filesystem or clock, so the word list gets supplied as
+//! bytes, and duration of each query is limited by number of steps.
+//!
+//! ```js
+//! const bytes = new Uint8Array(await (await fetch("words.txt")).arrayBuffer());
+//! const lexicon = new Lexicon(bytes, "en");
+//! lexicon.transpositions("listen");
+//! lexicon.anagrams("new south wales", 0, 100000, (phrase) => console.log(phrase));
+//! ```
+
+use js_sys::{Array, Function, JsString};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::config::Config;
+use crate::languages::Language;
+use crate::primes::PMap;
+use crate::search::Search;
+use crate::words::{self, Cache};
+
+/// Default value when maximum number of words is NOT specified
+const MIN_WORDS: usize = 3;
+
+/// Entire word list loaded once for many queries
+#[wasm_bindgen]
+pub struct Lexicon {
+    config: Config,
+    map: PMap,
+}
+
+#[wasm_bindgen]
+impl Lexicon {
+    /// Load word list from `bytes` containing one word per line as
+    /// UTF-8 for natural language `lang` (2 letter ISO code such as "en")
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8], lang: &str) -> Result<Lexicon, JsError> {
+        let lang = Language::from_str(lang)?;
+        let config = Config { lang, ..Config::default() };
+        let map = words::preload_from(
+            bytes,
+            &config.rules()?,
+            &config.encoding,
+            config.include_short,
+            config.include_upcase,
+            config.dedup(),
+            &config.blocklist()?,
+        )?;
+        Ok(Lexicon { config, map })
+    }
+
+    /// Number of distinct sets of letters within word list
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Single words containing exactly the same letters as `phrase`
+    pub fn transpositions(&self, phrase: &str) -> Result<Vec<String>, JsError> {
+        let input_phrase = split(phrase);
+        let search = Search::query(&input_phrase, &[], &self.config)?;
+        let (_, mut singles) = words::select(&self.map, &search.primes_product, &[]);
+        words::filter_multiword(&self.config, &mut singles);
+        Ok(singles)
+    }
+
+    /// Resolve `phrase` into multiple word anagrams, calling `on_phrase`
+    /// with each as it is found.  Each phrase is an array of terms, and
+    /// each term is an array of alternative words sharing same letters.
+    /// Returning `false` from `on_phrase` stops early.
+    ///
+    /// Limits: `max_words` of 0 defaults to one more than number of
+    /// words within `phrase` and minimum of 3; `max_steps` of 0 is
+    /// unlimited, otherwise use roughly 100000 for interactive pages.
+    ///
+    /// Returns number of phrases found.
+    pub fn anagrams(
+        &self, phrase: &str, max_words: usize, max_steps: usize, on_phrase: &Function,
+    ) -> Result<usize, JsError> {
+        let input_phrase = split(phrase);
+        let max_phrase_words = match max_words {
+            0 => std::cmp::max(input_phrase.len() + 1, MIN_WORDS),
+            n => n,
+        };
+        let config = Config { max_phrase_words, ..self.config.clone() };
+        let search = Search::query(&input_phrase, &[], &config)?;
+        if max_phrase_words < 2 {
+            return Ok(0);
+        }
+        let (dict, _) = words::select(&self.map, &search.primes_product, &[]);
+        let cache = Cache::init(&dict);
+        let builder =
+            search.add_cache(&cache).with_max_steps((max_steps > 0).then_some(max_steps));
+        let results = builder.brute_force_with(|phrase| {
+            let terms: Array = phrase
+                .iter()
+                .map(|alternatives| {
+                    alternatives
+                        .iter()
+                        .map(|w| JsString::from(w.as_str()))
+                        .collect::<Array>()
+                })
+                .collect();
+            match on_phrase.call1(&JsValue::NULL, &terms) {
+                Ok(proceed) => proceed.as_bool() != Some(false),
+                Err(_) => false,
+            }
+        });
+        Ok(results.len())
+    }
+}
+
+fn split(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(ToString::to_string).collect()
+}
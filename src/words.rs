@@ -1,14 +1,24 @@
 //! Load word lists with or without filtering.
 
+use glob::Pattern;
 use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+use std::borrow::Cow;
+use std::collections::{hash_map, BTreeMap, HashMap};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
 
+use crate::blocklist::Blocklist;
 use crate::config::Config;
-use crate::error::Result;
-use crate::languages::{self, Encoding, Language, SHORT, UPCASE};
+use crate::error::{AnagramError, Result};
+use crate::fold;
+use crate::hunspell;
+use crate::languages::{Casing, Encoding, Rules};
 use crate::primes::{self, PMap};
+use crate::sources::Sources;
 
 /// Cache of a word list and its metadata.
 ///
@@ -19,8 +29,20 @@ pub struct Cache<'a> {
     /// HashMap of prime to phrase
     pub lexicon: &'a PMap,
     /// Index into `lexicon` sorted by its keys (primes) high-to-low
-    /// because [std::collections;:BTreeMap] lacks Range support
+    /// because [std::collections;:BTreeMap] lacks Range support.
+    /// A key repeats when its words differ by [word_count].
     pub descending_keys: Vec<&'a BigUint>,
+    /// Words of each key within `descending_keys` at same position,
+    /// which are a subset of those within `lexicon` when key repeats
+    pub alternatives: Vec<Cow<'a, [String]>>,
+    /// Number of space separated words of each of `alternatives`,
+    /// where multi-word lexemes such as "ice cream" exceed one
+    pub word_counts: Vec<usize>,
+    /// Index of source of each of `alternatives` within [Sources::tags]
+    pub sources: Vec<usize>,
+    /// At most this many words of each source within a phrase, in
+    /// order of [Sources::tags], or empty when unlimited
+    pub limits: Vec<Option<usize>>,
 }
 
 impl<'a> Cache<'a> {
@@ -39,19 +61,73 @@ impl<'a> Cache<'a> {
     /// let mut builder = search.enrich(&cache, None);
     /// let mut anagrams = builder.brute_force();
     /// ```
-    pub fn init(map: &PMap) -> Cache {
-        let mut descending_keys: Vec<&BigUint> = map.keys().collect();
-        descending_keys.sort_by(|&a, &b| b.cmp(a));
+    pub fn init(map: &PMap) -> Cache<'_> {
+        Cache::group(map, |_| 0, vec![])
+    }
+
+    /// Same as [Cache::init] but also separating words by their source
+    /// such that `--limit` of each may be applied while searching.
+    pub fn with_sources<'s>(map: &'a PMap, sources: &'s Sources) -> Cache<'a> {
+        Cache::group(map, |word| sources.index(word).unwrap_or(0), sources.limits())
+    }
+
+    /// Repeat a key of `map` for each distinct combination of
+    /// [word_count] and source of its words.
+    fn group<F>(map: &'a PMap, source: F, limits: Vec<Option<usize>>) -> Cache<'a>
+    where
+        F: Fn(&str) -> usize,
+    {
+        let mut entries = vec![];
+        for (key, words) in map {
+            let group = |word: &str| (word_count(word), source(word));
+            let first = words.first().map_or((1, 0), |w| group(w));
+            if words.iter().all(|w| group(w) == first) {
+                entries.push((key, first, Cow::Borrowed(words.as_slice())));
+            } else {
+                // Separate single words from multi-word lexemes, etc.
+                let mut groups: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+                for word in words {
+                    groups.entry(group(word)).or_default().push(word.to_string());
+                }
+                for (group, words) in groups {
+                    entries.push((key, group, Cow::Owned(words)));
+                }
+            }
+        }
+        entries.sort_by(|a, b| b.0.cmp(a.0).then(a.1.cmp(&b.1)));
+        let mut descending_keys = Vec::with_capacity(entries.len());
+        let mut alternatives = Vec::with_capacity(entries.len());
+        let mut word_counts = Vec::with_capacity(entries.len());
+        let mut sources = Vec::with_capacity(entries.len());
+        for (key, (count, source), words) in entries {
+            descending_keys.push(key);
+            word_counts.push(count);
+            sources.push(source);
+            alternatives.push(words);
+        }
 
         // TODO: Apply modified sequence of primes to accommodate
         // letter frequency within locale specific $LANG, such that
         // more common words will be found first; e.g., ETAOIN SRHLDCU
         // in EN-US from https://norvig.com/mayzner.html
 
-        Cache { lexicon: map, descending_keys }
+        Cache {
+            lexicon: map,
+            descending_keys,
+            alternatives,
+            word_counts,
+            sources,
+            limits,
+        }
     }
 }
 
+/// Number of space separated words within a dictionary entry, such
+/// as 2 for the multi-word lexeme "ice cream" and 1 for "ice".
+pub fn word_count(word: &str) -> usize {
+    word.split_whitespace().count().max(1)
+}
+
 const NEWLINE: u8 = 0x0A;
 
 /// Filter while loading lists of natural language words: e.g., English.
@@ -70,8 +146,8 @@ const NEWLINE: u8 = 0x0A;
 /// - `primes_product` Mathematical product of all prime numbers representing `pattern`;
 /// - `lang` and `encoding` Language (e.g., EN=English), UTF-8/ISO-8859-1/etc;
 /// - `short` and `upcase` opt-in to allowing words that otherwise
-///    aren't idiomatic for `lang` (i.e., Booleans to allow more than
-///    'a' and 'I' for English.)
+///   aren't idiomatic for `lang` (i.e., Booleans to allow more than
+///   'a' and 'I' for English.)
 ///
 /// Returns tuple of 1) [PMap] containing words selected after initial
 /// filtering and 2) set of single word matches.
@@ -81,88 +157,356 @@ pub fn load_and_select(
     config: &Config, pattern: &str, essential: &str, primes_product: &BigUint,
     must_exclude: &[String],
 ) -> Result<(PMap, Vec<String>)> {
+    let (map, singles, _) = load_and_select_sources(
+        config,
+        pattern,
+        essential,
+        primes_product,
+        must_exclude,
+    )?;
+    Ok((map, singles))
+}
+
+/// Same as [load_and_select] but also returns [Sources] identifying
+/// the tagged word list from which each word was loaded.
+///
+/// See fn [Config::dict_sources] for tags.
+pub fn load_and_select_sources(
+    config: &Config, pattern: &str, essential: &str, primes_product: &BigUint,
+    must_exclude: &[String],
+) -> Result<(PMap, Vec<String>, Sources)> {
     let mut single_word_list = vec![];
     let mut map = PMap::new();
+    let rules = config.rules()?;
+    let blocklist = config.blocklist()?;
+    let mut seen = Seen::new(config.dedup());
+    let mut tags: Vec<String> = vec![];
+    for (tag, filepath) in config.dict_sources()? {
+        seen.source = tags.iter().position(|t| *t == tag).unwrap_or(tags.len());
+        if seen.source == tags.len() {
+            tags.push(tag);
+        }
+        let name = filepath.to_string_lossy();
+        let (reader, encoding) = open(&filepath, &config.encoding)?;
+        let lines = select_lines(
+            reader,
+            &name,
+            config,
+            &encoding,
+            pattern,
+            essential,
+            primes_product,
+            must_exclude,
+            &rules,
+            &blocklist,
+            &mut seen,
+            &mut map,
+            &mut single_word_list,
+        );
+        eprintln!(
+            "Word list: file={name} lines={lines}, filtered-entries={}, duplicates={}",
+            map.len(),
+            seen.duplicates
+        );
+    }
+    let sources = Sources::new(config, tags, seen.dedup, seen.keys);
+    Ok((map, single_word_list, sources))
+}
+
+/// Equivalent of [load_and_select] but reading word list from `reader`
+/// rather than files of `config.dict_file_paths`, such as when a word
+/// list is already in memory and there is no filesystem; e.g.,
+/// WebAssembly within a browser.  Likewise, `exclude_files`,
+/// `allow_files` and `rules_file` of `config` get read only when
+/// specified, so leave those empty where there is no filesystem.
+pub fn load_and_select_from<R: BufRead>(
+    reader: R, config: &Config, pattern: &str, essential: &str, primes_product: &BigUint,
+    must_exclude: &[String],
+) -> Result<(PMap, Vec<String>)> {
+    let mut single_word_list = vec![];
+    let mut map = PMap::new();
+    select_lines(
+        reader,
+        READER_NAME,
+        config,
+        &config.encoding,
+        pattern,
+        essential,
+        primes_product,
+        must_exclude,
+        &config.rules()?,
+        &config.blocklist()?,
+        &mut Seen::new(config.dedup()),
+        &mut map,
+        &mut single_word_list,
+    );
+    Ok((map, single_word_list))
+}
+
+/// Merging of words considered duplicates across all files loaded,
+/// beyond those that are identical.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Dedup {
+    /// Words differing only by upper/lower case are duplicates
+    pub case: bool,
+    /// Words differing only by diacritics are duplicates; see [fold]
+    pub diacritics: bool,
+    /// Lowercasing per language when `case`, such as Turkish dotless ı
+    pub casing: Casing,
+}
+
+impl Dedup {
+    /// Equivalent of `word` such that duplicates are identical
+    pub fn key(&self, word: &str) -> String {
+        // Lowercase prior to folding such that Turkish İ becomes i
+        let key = match self.case {
+            true => self.casing.lowercase(word),
+            false => word.to_string(),
+        };
+        match self.diacritics {
+            true => fold::diacritics(&key, self.casing),
+            false => key,
+        }
+    }
+}
+
+/// Words already loaded, for dropping duplicates across all files
+/// regardless of order within each.  The first spelling to pass the
+/// filters of its language and blocklist gets retained, regardless of
+/// whether it matches the query, such that the same words count as
+/// duplicates for every query.
+struct Seen {
+    dedup: Dedup,
+    /// Key per [Dedup::key] of each word and index of its source
+    keys: HashMap<String, usize>,
+    /// Index of source of words currently loading
+    source: usize,
+    /// Count of words dropped
+    duplicates: usize,
+}
+
+impl Seen {
+    fn new(dedup: Dedup) -> Seen {
+        Seen { dedup, keys: HashMap::new(), source: 0, duplicates: 0 }
+    }
+
+    /// True when `word` or its equivalent per [Dedup] was already seen
+    fn is_duplicate(&mut self, word: &str) -> bool {
+        match self.keys.entry(self.dedup.key(word)) {
+            hash_map::Entry::Occupied(_) => {
+                self.duplicates += 1;
+                true
+            }
+            hash_map::Entry::Vacant(e) => {
+                e.insert(self.source);
+                false
+            }
+        }
+    }
+}
+
+/// Name of word list in status and errors when not loaded from a file
+const READER_NAME: &str = "(reader)";
+
+/// File path indicating stdin for word list
+pub const STDIN_PATH: &str = "-";
+
+/// Replace each directory of `paths` with files inside it, excluding
+/// hidden files and Hunspell `.aff` files, and including only those
+/// whose file name matches `glob` when specified.
+///
+/// Files within each directory are sorted by name for deterministic
+/// order, and subdirectories are skipped.  Other paths such as
+/// [STDIN_PATH] are retained as specified.
+pub fn dict_files<P: AsRef<Path>>(
+    paths: &[P], glob: Option<&Pattern>,
+) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths.iter().map(AsRef::as_ref) {
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            continue;
+        }
+        let mut entries = vec![];
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let affixes = format!(".{}", hunspell::AFF_EXTENSION);
+            // Follows symbolic links
+            let path = entry.path();
+            if name.starts_with('.')
+                || name.ends_with(&affixes)
+                || name.contains(&format!("{affixes}."))
+                || !path.is_file()
+                || glob.is_some_and(|glob| !glob.matches(&name))
+            {
+                continue;
+            }
+            entries.push(path);
+        }
+        entries.sort();
+        files.extend(entries);
+    }
+    Ok(files)
+}
+
+/// Open word list file for reading one word per line, returning its
+/// reader and the encoding of what it yields.
+///
+/// A Hunspell `.dic` file with sibling `.aff` file gets expanded into
+/// all of its affixed forms as UTF-8; see [crate::hunspell].
+fn open(filepath: &Path, encoding: &Encoding) -> Result<(Box<dyn BufRead>, Encoding)> {
+    open_with_stdin(filepath, encoding, || std::io::stdin().lock())
+}
+
+/// Same as [open] but reading from what `stdin` returns for
+/// [STDIN_PATH], such as an in-memory buffer within tests.
+pub(crate) fn open_with_stdin<R, F>(
+    filepath: &Path, encoding: &Encoding, stdin: F,
+) -> Result<(Box<dyn BufRead>, Encoding)>
+where
+    R: BufRead + 'static,
+    F: FnOnce() -> R,
+{
+    if filepath == Path::new(STDIN_PATH) {
+        return Ok((Box::new(stdin()), encoding.clone()));
+    }
+    match hunspell::affix_file(filepath) {
+        Some(aff) => {
+            let words = hunspell::expand(filepath, &aff, encoding)?;
+            let text = words.join("\n").into_bytes();
+            Ok((Box::new(Cursor::new(text)), Encoding::Utf_8))
+        }
+        None => Ok((open_file(filepath)?, encoding.clone())),
+    }
+}
+
+/// File name extensions of compressed word lists, which are only
+/// relevant for finding sibling files such as Hunspell's `.aff`
+pub const COMPRESSED_EXTENSIONS: [&str; 3] = ["gz", "xz", "zst"];
+
+/// Leading bytes identifying compressed files, with name of the
+/// compile-time feature required for decompressing each
+const MAGIC: [(&[u8], &str); 3] = [
+    (&[0x1f, 0x8b], "gzip"),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], "xz"),
+    (&[0x28, 0xb5, 0x2f, 0xfd], "zstd"),
+];
+
+/// Open file for reading, transparently decompressing per magic bytes
+/// regardless of file name extension.
+///
+/// Decompressing requires compile-time feature `gzip`, `xz` or `zstd`
+/// respectively, otherwise [AnagramError::CompressionNotEnabled].
+pub fn open_file(filepath: &Path) -> Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(File::open(filepath)?);
+    let head = reader.fill_buf()?;
+    let format = MAGIC.iter().find(|(magic, _)| head.starts_with(magic));
+    match format.map(|(_, feature)| *feature) {
+        None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Some("gzip") => {
+            let decoder = libflate::gzip::MultiDecoder::new(reader)?;
+            Ok(Box::new(BufReader::new(decoder)))
+        }
+        #[cfg(feature = "xz")]
+        Some("xz") => {
+            let decoder = xz2::read::XzDecoder::new_multi_decoder(reader);
+            Ok(Box::new(BufReader::new(decoder)))
+        }
+        #[cfg(feature = "zstd")]
+        Some("zstd") => {
+            let decoder = zstd::stream::read::Decoder::with_buffer(reader)?;
+            Ok(Box::new(BufReader::new(decoder)))
+        }
+        Some(feature) => Err(AnagramError::CompressionNotEnabled(feature)),
+    }
+}
+
+/// Filter each line of `reader` per [load_and_select] accumulating
+/// into `map` and `single_word_list`.  Returns number of lines read.
+#[allow(clippy::too_many_arguments)]
+fn select_lines<R: BufRead>(
+    mut reader: R, name: &str, config: &Config, encoding: &Encoding, pattern: &str,
+    essential: &str, primes_product: &BigUint, must_exclude: &[String], rules: &Rules,
+    blocklist: &Blocklist, seen: &mut Seen, map: &mut PMap,
+    single_word_list: &mut Vec<String>,
+) -> usize {
     let input_length = essential.len();
-    let empty: Vec<&str> = vec![];
-    let short_words = SHORT.get(&config.lang).unwrap_or(&empty);
-    let upcase_words = UPCASE.get(&config.lang).unwrap_or(&empty);
+    let alphabet = config.alphabet();
+    // Same canonical form as dictionary words for comparing
+    let must_exclude: Vec<String> =
+        must_exclude.iter().map(|w| alphabet.normalized(w.clone())).collect();
     let mut bytes: Vec<u8> = vec![];
-    for filepath in config.dict_file_paths.iter() {
-        let mut fd = BufReader::new(File::open(filepath)?);
-        let mut previous = String::new();
-        let mut i = 0;
-        loop {
-            i += 1;
-            bytes.clear();
-            match fd.read_until(NEWLINE, &mut bytes) {
-                Ok(0) => break, // End of file (EOF)
-                Ok(_n) => {
-                    let mut word = if config.encoding == Encoding::Iso_8859_1 {
-                        bytes.iter().map(|&x| char::from(x)).collect()
+    let mut i = 0;
+    loop {
+        i += 1;
+        bytes.clear();
+        match reader.read_until(NEWLINE, &mut bytes) {
+            Ok(0) => break, // End of file (EOF)
+            Ok(_n) => {
+                let word = if *encoding == Encoding::Iso_8859_1 {
+                    bytes.iter().map(|&x| char::from(x)).collect()
+                } else {
+                    String::from_utf8_lossy(&bytes).to_string()
+                }
+                .trim()
+                .to_string();
+                let word = alphabet.normalized(word);
+                if word.is_empty() {
+                    continue;
+                }
+                if must_exclude.contains(&word) {
+                    continue;
+                }
+                if config.no_multiword && word_count(&word) > 1 {
+                    continue;
+                }
+                if blocklist.is_blocked(&word) {
+                    continue;
+                }
+                if !blocklist.is_allowed(&word)
+                    && rules.filter(&word, config.include_short, config.include_upcase)
+                {
+                    continue;
+                }
+                if seen.is_duplicate(&word) {
+                    continue;
+                }
+                if let Ok(product) = primes::filter_word(
+                    &word,
+                    pattern,
+                    input_length,
+                    primes_product,
+                    alphabet,
+                ) {
+                    if product == *primes_product {
+                        // This dictionary word matches exactly.
+                        single_word_list.push(word.to_string());
                     } else {
-                        String::from_utf8_lossy(&bytes).to_string()
+                        // Store remaining words in look-up table as sorted list:
+                        #[allow(clippy::vec_init_then_push)]
+                        map.entry(product)
+                            .and_modify(|e| {
+                                e.push(word.to_string());
+                                e.sort_unstable();
+                            })
+                            .or_insert_with(|| {
+                                // Anticipate lots of words with unique products
+                                let mut v = Vec::with_capacity(1);
+                                v.push(word.to_string());
+                                v
+                            });
                     }
-                    .trim()
-                    .to_string();
-                    if word.is_empty() {
-                        continue;
-                    }
-                    if word == previous {
-                        continue;
-                    }
-                    if must_exclude.iter().any(|w| *w == word) {
-                        continue;
-                    }
-                    if languages::filter(
-                        &word,
-                        short_words,
-                        upcase_words,
-                        config.include_short,
-                        config.include_upcase,
-                    ) {
-                        continue;
-                    }
-                    if let Ok(product) =
-                        primes::filter_word(&word, pattern, input_length, primes_product)
-                    {
-                        if product == *primes_product {
-                            // This dictionary word matches exactly.
-                            single_word_list.push(word.to_string());
-                        } else {
-                            // Store remaining words in look-up table as sorted list:
-                            #[allow(clippy::vec_init_then_push)]
-                            map.entry(product)
-                                .and_modify(|e| {
-                                    e.push(word.to_string());
-                                    e.sort_unstable();
-                                })
-                                .or_insert_with(|| {
-                                    // Anticipate lots of words with unique products
-                                    let mut v = Vec::with_capacity(1);
-                                    v.push(word.to_string());
-                                    v
-                                });
-                        }
-                    }
-                    std::mem::swap(&mut previous, &mut word);
-                }
-                Err(e) => {
-                    println!(
-                        "File error: file={} line={i} {e:?}",
-                        filepath.to_string_lossy()
-                    );
-                    break;
                 }
             }
+            Err(e) => {
+                eprintln!("File error: file={name} line={i} {e:?}");
+                break;
+            }
         }
-        println!(
-            "Word list: file={} lines={i}, filtered-entries={}",
-            filepath.to_string_lossy(),
-            map.len()
-        );
     }
-    Ok((map, single_word_list))
+    i
 }
 
 /// Load ENTIRE word list suitable for caching and WITHOUT filtering.
@@ -176,68 +520,203 @@ pub fn load_and_select(
 /// Returns [PMap] containing mathematical product of primes
 /// associated with list of words with that product.
 ///
-/// See also: fn [load_and_select].
+/// See also: fn [load_and_select] and fn [select].
+#[allow(clippy::too_many_arguments)]
 pub fn preload(
-    file_paths: &[&Path], lang: &Language, encoding: &Encoding, short: bool,
-    upcase: bool, verbose: bool,
+    file_paths: &[&Path], rules: &Rules, encoding: &Encoding, short: bool, upcase: bool,
+    dedup: Dedup, blocklist: &Blocklist, verbose: bool,
+) -> Result<PMap> {
+    let mut map = PMap::new();
+    let mut seen = Seen::new(dedup);
+    for filepath in dict_files(file_paths, None)? {
+        let name = filepath.to_string_lossy();
+        let (reader, encoding) = open(&filepath, encoding)?;
+        let lines = preload_lines(
+            reader, &name, rules, &encoding, short, upcase, blocklist, &mut seen,
+            &mut map,
+        )?;
+        if verbose {
+            eprintln!(
+                "Word list: file={name} lines={lines}, filtered-entries={}, duplicates={}",
+                map.len(),
+                seen.duplicates
+            );
+        }
+    }
+    for words in map.values_mut() {
+        words.sort_unstable();
+    }
+    Ok(map)
+}
+
+/// Same as [preload] but per `config` such as via CLI, including tags
+/// of its word lists as [Sources] for `--weight` and `--limit`.
+///
+/// See fn [Config::dict_sources] for tags.
+pub fn preload_sources(config: &Config, verbose: bool) -> Result<(PMap, Sources)> {
+    let rules = config.rules()?;
+    let blocklist = config.blocklist()?;
+    let mut map = PMap::new();
+    let mut seen = Seen::new(config.dedup());
+    let mut tags: Vec<String> = vec![];
+    for (tag, filepath) in config.dict_sources()? {
+        seen.source = tags.iter().position(|t| *t == tag).unwrap_or(tags.len());
+        if seen.source == tags.len() {
+            tags.push(tag);
+        }
+        let name = filepath.to_string_lossy();
+        let (reader, encoding) = open(&filepath, &config.encoding)?;
+        let lines = preload_lines(
+            reader,
+            &name,
+            &rules,
+            &encoding,
+            config.include_short,
+            config.include_upcase,
+            &blocklist,
+            &mut seen,
+            &mut map,
+        )?;
+        if verbose {
+            eprintln!(
+                "Word list: file={name} lines={lines}, filtered-entries={}, duplicates={}",
+                map.len(),
+                seen.duplicates
+            );
+        }
+    }
+    for words in map.values_mut() {
+        words.sort_unstable();
+    }
+    let sources = Sources::new(config, tags, seen.dedup, seen.keys);
+    Ok((map, sources))
+}
+
+/// Equivalent of [preload] but reading word list from `reader` rather
+/// than files, such as when a word list is already in memory and there
+/// is no filesystem; e.g., WebAssembly within a browser.
+pub fn preload_from<R: BufRead>(
+    reader: R, rules: &Rules, encoding: &Encoding, short: bool, upcase: bool,
+    dedup: Dedup, blocklist: &Blocklist,
 ) -> Result<PMap> {
     let mut map = PMap::new();
-    let empty: Vec<&str> = vec![];
-    let short_words = SHORT.get(lang).unwrap_or(&empty);
-    let upcase_words = UPCASE.get(lang).unwrap_or(&empty);
+    let mut seen = Seen::new(dedup);
+    preload_lines(
+        reader,
+        READER_NAME,
+        rules,
+        encoding,
+        short,
+        upcase,
+        blocklist,
+        &mut seen,
+        &mut map,
+    )?;
+    for words in map.values_mut() {
+        words.sort_unstable();
+    }
+    Ok(map)
+}
+
+/// Hash each line of `reader` per [preload] accumulating into `map`.
+/// Returns number of lines read.
+#[allow(clippy::too_many_arguments)]
+fn preload_lines<R: BufRead>(
+    mut reader: R, name: &str, rules: &Rules, encoding: &Encoding, short: bool,
+    upcase: bool, blocklist: &Blocklist, seen: &mut Seen, map: &mut PMap,
+) -> Result<usize> {
     let mut bytes: Vec<u8> = vec![];
-    for filepath in file_paths {
-        let mut word = String::new();
-        let mut previous = String::new();
-        let mut i = 0;
-        loop {
-            i += 1;
-            bytes.clear();
-            word.clear();
-            let mut f = BufReader::new(File::open(filepath)?);
-            match f.read_until(0x0A, &mut bytes) {
-                Ok(0) => break, // End of file (EOF)
-                Ok(_n) => {
-                    if *encoding == Encoding::Iso_8859_1 {
-                        word = bytes.iter().map(|&x| char::from(x)).collect();
-                    } else {
-                        word = String::from_utf8_lossy(&bytes).to_string();
-                    }
-                    word = word.trim().to_string();
-                    if word.is_empty() {
-                        continue;
-                    }
-                    if word == previous {
-                        continue;
-                    }
-                    if languages::filter(&word, short_words, upcase_words, short, upcase)
-                    {
-                        continue;
+    let mut word;
+    let mut i = 0;
+    loop {
+        i += 1;
+        bytes.clear();
+        match reader.read_until(NEWLINE, &mut bytes) {
+            Ok(0) => break, // End of file (EOF)
+            Ok(_n) => {
+                if *encoding == Encoding::Iso_8859_1 {
+                    word = bytes.iter().map(|&x| char::from(x)).collect();
+                } else {
+                    word = String::from_utf8_lossy(&bytes).to_string();
+                }
+                word = rules.alphabet().normalized(word.trim().to_string());
+                if word.is_empty() {
+                    continue;
+                }
+                if blocklist.is_blocked(&word) {
+                    continue;
+                }
+                if !blocklist.is_allowed(&word) && rules.filter(&word, short, upcase) {
+                    continue;
+                }
+                if seen.is_duplicate(&word) {
+                    continue;
+                }
+                let essential = primes::essential_chars(&word, rules.alphabet());
+                // Skip words lacking any alphabetic chars or containing
+                // chars beyond those accommodated by the hasher
+                if !essential.is_empty() {
+                    if let Ok(primes) = primes::primes(&essential, rules.alphabet()) {
+                        let product = primes::primes_product(&primes)?;
+                        map.entry(product)
+                            .or_insert_with(|| Vec::with_capacity(1))
+                            .push(word.to_string());
                     }
-                    let essential = primes::essential_chars(&word);
-                    let primes = primes::primes(&essential)?;
-                    let product = primes::primes_product(&primes)?;
-                    map.entry(product)
-                        .or_insert_with(|| Vec::with_capacity(1))
-                        .push(word.to_string());
-                    std::mem::swap(&mut previous, &mut word);
-                }
-                Err(e) => {
-                    println!(
-                        "File error: file={} line={i} {e:?}",
-                        filepath.to_string_lossy()
-                    );
-                    break;
                 }
             }
+            Err(e) => {
+                eprintln!("File error: file={name} line={i} {e:?}");
+                break;
+            }
         }
-        if verbose {
-            println!(
-                "Word list: file={} lines={i}, filtered-entries={}",
-                filepath.to_string_lossy(),
-                map.len()
-            );
+    }
+    Ok(i)
+}
+
+/// Remove multi-word lexemes from `transpositions` per `--no-multiword`
+/// of `config`, such as after [select] from a word list previously
+/// loaded via [preload], which retains them.
+pub fn filter_multiword(config: &Config, transpositions: &mut Vec<String>) {
+    if config.no_multiword {
+        transpositions.retain(|word| word_count(word) == 1);
+    }
+}
+
+/// True when `word` is within `must_exclude`, regardless of Unicode
+/// canonical form of either, since that of a preloaded word list is
+/// unknown here
+fn is_excluded(word: &str, must_exclude: &[String]) -> bool {
+    must_exclude.iter().any(|w| w == word || w.nfc().eq(word.nfc()))
+}
+
+/// Filter a word list previously loaded via [preload] for a single
+/// query, yielding the same results as [load_and_select] would but
+/// without reading any files.
+///
+/// Params with same name have identical semantics as [load_and_select].
+///
+/// Returns tuple of 1) [PMap] containing words selected and 2) set of
+/// single word matches.
+pub fn select(
+    lexicon: &PMap, primes_product: &BigUint, must_exclude: &[String],
+) -> (PMap, Vec<String>) {
+    let mut single_word_list = vec![];
+    let mut map = PMap::new();
+    // Divisibility implies both length and chars are within those of query
+    for (product, words) in lexicon.range(..=primes_product) {
+        if product.is_one() || !primes_product.is_multiple_of(product) {
+            continue;
+        }
+        let words = words.iter().filter(|&w| !is_excluded(w, must_exclude)).cloned();
+        if product == primes_product {
+            // These dictionary words match exactly.
+            single_word_list.extend(words);
+        } else {
+            let words: Vec<String> = words.collect();
+            if !words.is_empty() {
+                map.insert(product.clone(), words);
+            }
         }
     }
-    Ok(map)
+    (map, single_word_list)
 }
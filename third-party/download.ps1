@@ -20,5 +20,18 @@ Invoke-RestMethod -Uri 'http://app.aspell.net/create?max_size=60&spelling=US&max
 
 Expand-Archive en_US/SCOWL-wl.zip -DestinationPath en_US
 
+# Hunspell dictionaries for other languages are from LibreOffice, with each
+# `.dic` file expanded via its sibling `.aff` file while loading.
+
+$LO = 'https://raw.githubusercontent.com/LibreOffice/dictionaries/master'
+
+foreach ($dict in 'de/de_DE_frami', 'it_IT/it_IT', 'nl_NL/nl_NL', 'pt_PT/pt_PT') {
+    $dir = (Split-Path $dict -Leaf).Substring(0, 5)
+    $file = Split-Path $dict -Leaf
+    New-Item -Path . -Name $dir -ItemType "directory" -ErrorAction SilentlyContinue
+    Invoke-RestMethod -Uri "$LO/$dict.dic" -Method Get -OutFile "$dir/$file.dic"
+    Invoke-RestMethod -Uri "$LO/$dict.aff" -Method Get -OutFile "$dir/$file.aff"
+}
+
 Write-Host 'Assets for test suite have been downloaded.'
 Write-Host 'From top-level folder for this repo, run: cargo test'
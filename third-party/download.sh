@@ -36,5 +36,15 @@ tar zxf en_CA/SCOWL-wl.tar.gz -C en_CA
 (cd en_US/ && $GET $EN_US)
 tar zxf en_US/SCOWL-wl.tar.gz -C en_US
 
+# Hunspell dictionaries for other languages are from LibreOffice, with each
+# `.dic` file expanded via its sibling `.aff` file while loading.
+LO='https://raw.githubusercontent.com/LibreOffice/dictionaries/master'
+
+for dict in de/de_DE_frami it_IT/it_IT nl_NL/nl_NL pt_PT/pt_PT; do
+    dir=$(basename $dict | cut -c1-5)
+    [ -d $dir ] || mkdir $dir
+    (cd $dir/ && $GET $LO/$dict.dic && $GET $LO/$dict.aff)
+done
+
 echo 'Assets for test suite have been downloaded.'
 echo 'From top-level directory for this repo, run: cargo test'